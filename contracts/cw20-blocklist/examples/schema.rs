@@ -4,7 +4,9 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use cw20::{AllowanceResponse, BalanceResponse, TokenInfoResponse};
-use cw20_blocklist::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw20_blocklist::msg::{
+    ExecuteMsg, InstantiateMsg, MaxBalanceResponse, PendingActionsResponse, QueryMsg,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -18,4 +20,6 @@ fn main() {
     export_schema(&schema_for!(AllowanceResponse), &out_dir);
     export_schema(&schema_for!(BalanceResponse), &out_dir);
     export_schema(&schema_for!(TokenInfoResponse), &out_dir);
+    export_schema(&schema_for!(MaxBalanceResponse), &out_dir);
+    export_schema(&schema_for!(PendingActionsResponse), &out_dir);
 }
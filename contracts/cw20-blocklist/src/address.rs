@@ -0,0 +1,114 @@
+use cosmwasm_std::{Addr, Deps, StdError, StdResult};
+
+use crate::error::ContractError;
+use crate::state::ADDRESS_PREFIX;
+
+/// Turns a user-supplied address into its canonical form.
+///
+/// Input is lowercased (bech32 is case-insensitive, but mixing cases is not a valid encoding),
+/// checked against the configured human-readable prefix, and round-tripped through
+/// `addr_canonicalize`/`addr_humanize` so that two spellings of one account always map to the
+/// same storage key.
+pub fn normalize(deps: Deps, input: &str) -> Result<Addr, ContractError> {
+    let invalid = || ContractError::InvalidAddress {
+        address: input.to_string(),
+    };
+
+    let has_upper = input.chars().any(|c| c.is_ascii_uppercase());
+    let has_lower = input.chars().any(|c| c.is_ascii_lowercase());
+    if has_upper && has_lower {
+        return Err(invalid());
+    }
+    let lowered = input.to_lowercase();
+
+    if let Some(prefix) = ADDRESS_PREFIX.may_load(deps.storage)? {
+        if !lowered.starts_with(&format!("{}1", prefix)) {
+            return Err(ContractError::InvalidAddressPrefix {
+                address: input.to_string(),
+                prefix,
+            });
+        }
+    }
+
+    let canonical = deps
+        .api
+        .addr_canonicalize(&lowered)
+        .map_err(|_| invalid())?;
+    let human = deps.api.addr_humanize(&canonical).map_err(|_| invalid())?;
+    if human.as_str() != lowered {
+        return Err(invalid());
+    }
+    Ok(human)
+}
+
+/// [`normalize`] for query handlers, which report errors as `StdError`.
+pub fn normalize_query(deps: Deps, input: &str) -> StdResult<Addr> {
+    normalize(deps, input).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::mock_dependencies;
+
+    use super::*;
+
+    #[test]
+    fn lowercases_single_case_input() {
+        let deps = mock_dependencies();
+        assert_eq!(
+            normalize(deps.as_ref(), "ADDR0000").unwrap(),
+            Addr::unchecked("addr0000")
+        );
+        assert_eq!(
+            normalize(deps.as_ref(), "addr0000").unwrap(),
+            Addr::unchecked("addr0000")
+        );
+    }
+
+    #[test]
+    fn rejects_mixed_case_and_malformed_input() {
+        let deps = mock_dependencies();
+        assert_eq!(
+            normalize(deps.as_ref(), "AddR0000").unwrap_err(),
+            ContractError::InvalidAddress {
+                address: "AddR0000".to_string()
+            }
+        );
+        assert_eq!(
+            normalize(deps.as_ref(), "xy").unwrap_err(),
+            ContractError::InvalidAddress {
+                address: "xy".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn enforces_configured_prefix() {
+        let mut deps = mock_dependencies();
+        ADDRESS_PREFIX
+            .save(deps.as_mut().storage, &"terra".to_string())
+            .unwrap();
+
+        let terra = "terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8";
+        assert_eq!(
+            normalize(deps.as_ref(), &terra.to_uppercase()).unwrap(),
+            Addr::unchecked(terra)
+        );
+        assert_eq!(
+            normalize(
+                deps.as_ref(),
+                "cosmos1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8"
+            )
+            .unwrap_err(),
+            ContractError::InvalidAddressPrefix {
+                address: "cosmos1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8".to_string(),
+                prefix: "terra".to_string(),
+            }
+        );
+        // The prefix must be followed by the bech32 separator.
+        assert!(matches!(
+            normalize(deps.as_ref(), "terraform"),
+            Err(ContractError::InvalidAddressPrefix { .. })
+        ));
+    }
+}
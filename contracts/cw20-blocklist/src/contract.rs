@@ -1,10 +1,13 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    attr, coins, to_binary, to_vec, Addr, Attribute, BankMsg, Binary, Deps, DepsMut, Env, Event,
+    MessageInfo, Order, Response, StdError, StdResult, Storage, Timestamp, Uint128,
 };
 
+use cw0::must_pay;
 use cw2::set_contract_version;
+use cw20::{AllowanceInfo, AllowanceResponse, BalanceResponse, Expiration};
 use cw20_base::allowances::{
     execute_decrease_allowance, execute_increase_allowance, execute_send_from,
     execute_transfer_from, query_allowance,
@@ -14,16 +17,44 @@ use cw20_base::contract::{
     query_token_info,
 };
 use cw20_base::enumerable::{query_all_accounts, query_all_allowances};
-use cw20_base::state::{MinterData, TokenInfo, BALANCES, TOKEN_INFO};
+use cw20_base::state::{MinterData, TokenInfo, ALLOWANCES, BALANCES, TOKEN_INFO};
+use cw_storage_plus::Bound;
 
+use crate::address::{normalize, normalize_query};
 use crate::error::ContractError;
-use crate::msg::{BlockedResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::state::BLOCKED;
+use crate::ibc::{blocklist_update_msgs, query_ibc_channels, set_ibc_peer};
+use crate::msg::{
+    AccountStatusResponse, ApproversResponse, AttestationInfo, AttestationsResponse, BlockRecord,
+    BlockedResponse, CanTransferResponse, ExecuteMsg, HoldInfo, HoldStatus, HoldsResponse,
+    InstantiateMsg, LatestAttestationResponse, MaxBalanceResponse, MigrateMsg, NonceResponse,
+    PendingAction, PendingActionsResponse, PermitPayload, ProposalAction, ProposalResponse,
+    ProposalStatus, ProposalVotesResponse, ProposalsResponse, QueryMsg, ReceivedAllowance,
+    RemoteOrigin, RequestInfo, RequestKind, RequestStatus, RequestsResponse, ReservesResponse,
+    Restriction, RestrictionMessageResponse, ReviewDecision, ReviewInfo, ReviewStatus,
+    ReviewsResponse, Role, RoleMembersResponse, SendWhitelistResponse, SignedDocument, SignedMsg,
+    SignedPayload, StatsResponse, TimelockAction, TimelockResponse, TotalSupplyResponse,
+    VestingSchedule, VestingScheduleResponse, VoteInfo, RESTRICTION_SUCCESS,
+};
+use crate::signature::{use_nonce, verify_signature};
+use crate::state::{
+    holds, issuance_requests, reviews, ApprovalConfig, Attestation, FeeConfig, Hold,
+    IssuanceRequest, Proposal, Review, ScheduledAction, Stats, TimelockConfig, Vesting,
+    ADDRESS_PREFIX, ALLOWANCE_SPENDERS, APPROVAL_CONFIG, ATTESTATIONS, BALANCE_SNAPSHOTS, BLOCKED,
+    BLOCK_RECORDS, ENFORCE_RESERVES, FEE_CONFIG, FEE_EXEMPT, MAX_BALANCE, MAX_BALANCE_EXEMPT,
+    NEXT_ACTION_ID, NEXT_ATTESTATION_ID, NEXT_HOLD_ID, NEXT_PROPOSAL_ID, NEXT_REQUEST_ID,
+    NEXT_REVIEW_ID, NONCES, OPEN_PROPOSALS, PROPOSALS, ROLES, SCHEDULED_ACTIONS, SEND_WHITELIST,
+    SEND_WHITELIST_ENABLED, STATS, TIMELOCK, TOTAL_SUPPLY_SNAPSHOTS, VESTING, VOTES, WRAPPED,
+    WRAP_DENOM,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw20-blocklist";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -57,14 +88,42 @@ pub fn execute(
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    dispatch(deps, env, info, msg)
+}
+
+fn dispatch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     // Execute messages that require a minter.
     match msg {
         ExecuteMsg::AddToBlockedList { .. }
         | ExecuteMsg::RemoveFromBlockedList { .. }
         | ExecuteMsg::UpdateMinter { .. }
+        | ExecuteMsg::UpdateMintCap { .. }
+        | ExecuteMsg::UpdateTimelock { .. }
+        | ExecuteMsg::Schedule { .. }
+        | ExecuteMsg::ExecuteScheduled { .. }
+        | ExecuteMsg::UpdateApprovers { .. }
+        | ExecuteMsg::SetRole { .. }
+        | ExecuteMsg::RecoverAccount { .. }
+        | ExecuteMsg::SetIbcPeer { .. }
+        | ExecuteMsg::UpdateWrapDenom { .. }
+        | ExecuteMsg::UpdateReserveEnforcement { .. }
+        | ExecuteMsg::UpdateAddressPrefix { .. }
+        | ExecuteMsg::UpdateMaxBalance { .. }
+        | ExecuteMsg::SetMaxBalanceExempt { .. }
+        | ExecuteMsg::UpdateFeeConfig { .. }
+        | ExecuteMsg::SetFeeExempt { .. }
+        | ExecuteMsg::UpdateSendWhitelist { .. }
+        | ExecuteMsg::SetSendWhitelisted { .. }
         | ExecuteMsg::Redeem { .. }
-        | ExecuteMsg::DestroyBlockedFunds { .. } => {
+        | ExecuteMsg::MintVested { .. }
+        | ExecuteMsg::DestroyBlockedFunds { .. }
+        | ExecuteMsg::DestroyUnvestedFunds { .. } => {
             let config = TOKEN_INFO.load(deps.storage)?;
             if config.mint.is_none() || config.mint.as_ref().unwrap().minter != info.sender {
                 return Err(ContractError::Unauthorized {});
@@ -74,97 +133,293 @@ pub fn execute(
     };
 
     match msg {
-        ExecuteMsg::AddToBlockedList { address } => Ok(try_add_to_blocklist(deps, address)?),
+        ExecuteMsg::AddToBlockedList { address } => Ok(try_add_to_blocklist(deps, env, address)?),
         ExecuteMsg::RemoveFromBlockedList { address } => {
-            Ok(try_remove_from_blocklist(deps, address)?)
+            run_admin_action(deps, env, TimelockAction::RemoveFromBlockedList { address })
+        }
+        ExecuteMsg::UpdateMinter { address } => {
+            if approvals_required(deps.storage)? {
+                return Err(ContractError::ApprovalRequired {});
+            }
+            run_admin_action(deps, env, TimelockAction::UpdateMinter { address })
+        }
+        ExecuteMsg::UpdateMintCap { cap } => {
+            run_admin_action(deps, env, TimelockAction::UpdateMintCap { cap })
         }
-        ExecuteMsg::UpdateMinter { address } => Ok(update_minter(deps, address)?),
-        ExecuteMsg::Mint { recipient, amount } => Ok(execute_mint(
+        ExecuteMsg::UpdateTimelock { delay, guardian } => run_admin_action(
             deps,
             env,
-            info,
-            recipient.to_lowercase(),
+            TimelockAction::UpdateTimelock { delay, guardian },
+        ),
+        ExecuteMsg::Schedule { action, eta } => schedule_action(deps, env, action, eta),
+        ExecuteMsg::ExecuteScheduled { id } => execute_scheduled(deps, env, id),
+        ExecuteMsg::Cancel { id } => cancel_scheduled(deps, info, id),
+        ExecuteMsg::UpdateApprovers {
+            approvers,
+            threshold,
+            voting_period,
+        } => {
+            if approvals_required(deps.storage)? {
+                return Err(ContractError::ApprovalRequired {});
+            }
+            update_approvers(deps, approvers, threshold, voting_period)
+        }
+        ExecuteMsg::Propose { action } => propose(deps, env, info, action),
+        ExecuteMsg::Vote {
+            proposal_id,
+            approve,
+        } => vote(deps, env, info, proposal_id, approve),
+        ExecuteMsg::SetRole {
+            role,
+            address,
+            granted,
+        } => {
+            if role == Role::Controller && granted && approvals_required(deps.storage)? {
+                return Err(ContractError::ApprovalRequired {});
+            }
+            set_role(deps, role, address, granted)
+        }
+        ExecuteMsg::RequestReview { evidence_uri } => request_review(deps, env, info, evidence_uri),
+        ExecuteMsg::ResolveReview {
+            address,
+            decision,
+            note,
+        } => resolve_review(deps, env, info, address, decision, note),
+        ExecuteMsg::RecoverAccount {
+            old,
+            new,
+            reference,
+        } => {
+            if approvals_required(deps.storage)? {
+                return Err(ContractError::ApprovalRequired {});
+            }
+            recover_account(deps, env, old, new, reference)
+        }
+        ExecuteMsg::ForceTransfer {
+            from,
+            to,
+            amount,
+            legal_reference,
+        } => force_transfer(deps, env, info, from, to, amount, legal_reference),
+        ExecuteMsg::RequestMint { amount, reference } => {
+            open_request(deps, env, info, RequestKind::Mint, amount, reference)
+        }
+        ExecuteMsg::RequestRedeem { amount, reference } => {
+            open_request(deps, env, info, RequestKind::Redeem, amount, reference)
+        }
+        ExecuteMsg::FulfilRequest { id } => fulfil_request(deps, env, info, id),
+        ExecuteMsg::Hold {
+            to,
             amount,
+            expires,
+        } => place_hold(deps, env, info, to, amount, expires),
+        ExecuteMsg::Release { id } => release_hold(deps, env, info, id),
+        ExecuteMsg::Revert { id } => revert_hold(deps, env, info, id),
+        ExecuteMsg::RejectRequest { id, reason } => reject_request(deps, env, info, id, reason),
+        ExecuteMsg::SubmitAttestation {
+            reserves,
+            as_of,
+            report_uri,
+            hash,
+        } => submit_attestation(deps, env, info, reserves, as_of, report_uri, hash),
+        ExecuteMsg::UpdateReserveEnforcement { enabled } => {
+            Ok(update_reserve_enforcement(deps, enabled)?)
+        }
+        ExecuteMsg::UpdateWrapDenom { denom } => update_wrap_denom(deps, denom),
+        ExecuteMsg::Wrap {} => wrap(deps, env, info),
+        ExecuteMsg::Unwrap { amount } => unwrap(deps, env, info, amount),
+        ExecuteMsg::SetIbcPeer {
+            connection_id,
+            port_id,
+            trusted,
+        } => Ok(set_ibc_peer(deps, connection_id, port_id, trusted)?),
+        ExecuteMsg::UpdateAddressPrefix { prefix } => Ok(update_address_prefix(deps, prefix)?),
+        ExecuteMsg::UpdateMaxBalance { max_balance } => Ok(update_max_balance(deps, max_balance)?),
+        ExecuteMsg::SetMaxBalanceExempt { address, exempt } => {
+            Ok(set_max_balance_exempt(deps, address, exempt)?)
+        }
+        ExecuteMsg::UpdateFeeConfig {
+            collector,
+            basis_points,
+            min_fee,
+            max_fee,
+        } => Ok(update_fee_config(
+            deps,
+            collector,
+            basis_points,
+            min_fee,
+            max_fee,
         )?),
+        ExecuteMsg::SetFeeExempt { address, exempt } => Ok(set_fee_exempt(deps, address, exempt)?),
+        ExecuteMsg::UpdateSendWhitelist { enabled } => Ok(update_send_whitelist(deps, enabled)?),
+        ExecuteMsg::SetSendWhitelisted {
+            contract,
+            whitelisted,
+        } => Ok(set_send_whitelisted(deps, contract, whitelisted)?),
+        ExecuteMsg::Mint { recipient, amount } => {
+            let recipient = normalize(deps.as_ref(), &recipient)?;
+            check_max_balance(deps.as_ref(), None, &recipient, amount)?;
+            check_reserves(deps.as_ref(), amount)?;
+            let res = mint_tokens(deps.branch(), env, info, &recipient, amount)?;
+            update_stats(deps.storage, |stats| {
+                stats.minted = stats.minted.checked_add(amount)?;
+                Ok(())
+            })?;
+            Ok(res)
+        }
+        ExecuteMsg::MintVested {
+            recipient,
+            amount,
+            schedule,
+        } => mint_vested(deps, env, info, recipient, amount, schedule),
         // these all come from cw20-base to implement the cw20 standard
         ExecuteMsg::Transfer { recipient, amount } => {
-            if is_blocked(deps.as_ref(), info.sender.to_string()).unwrap_or_default() {
+            let sender = normalize(deps.as_ref(), info.sender.as_str())?;
+            let recipient = normalize(deps.as_ref(), &recipient)?;
+            let fee = check_transfer(deps.as_ref(), &env, &sender, &recipient, amount, false)?;
+            check_funds(deps.as_ref(), &env, &sender, None, amount)?;
+            let amount = amount.checked_sub(fee.amount).map_err(StdError::from)?;
+
+            let res =
+                transfer_tokens(deps.branch(), env.clone(), info.clone(), &recipient, amount)?;
+            if let Some(collector) = &fee.collector {
+                transfer_tokens(deps, env, info, collector, fee.amount)?;
+            }
+            Ok(res
+                .add_attributes(fee.attributes())
+                .add_attribute("restriction_code", RESTRICTION_SUCCESS.to_string()))
+        }
+        ExecuteMsg::Redeem { amount } => {
+            let sender = normalize(deps.as_ref(), info.sender.as_str())?;
+            if is_blocked(deps.as_ref(), &sender)? {
                 return Err(ContractError::Blocked {});
             }
-
-            Ok(execute_transfer(
-                deps,
-                env,
-                info,
-                recipient.to_lowercase(),
-                amount,
-            )?)
+            check_unlocked(deps.as_ref(), &env, &sender, amount)?;
+            let res = burn_tokens(deps.branch(), env, info, amount)?;
+            update_stats(deps.storage, |stats| {
+                stats.redeemed = stats.redeemed.checked_add(amount)?;
+                Ok(())
+            })?;
+            Ok(res)
         }
-        ExecuteMsg::Redeem { amount } => Ok(execute_burn(deps, env, info, amount)?),
         ExecuteMsg::Send {
             contract,
             amount,
             msg,
         } => {
-            if is_blocked(deps.as_ref(), info.sender.to_string()).unwrap_or_default() {
-                return Err(ContractError::Blocked {});
+            let sender = normalize(deps.as_ref(), info.sender.as_str())?;
+            let contract = normalize(deps.as_ref(), &contract)?;
+            let fee = check_transfer(deps.as_ref(), &env, &sender, &contract, amount, true)?;
+            check_funds(deps.as_ref(), &env, &sender, None, amount)?;
+            let amount = amount.checked_sub(fee.amount).map_err(StdError::from)?;
+
+            let res = send_tokens(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                &contract,
+                amount,
+                msg,
+            )?;
+            if let Some(collector) = &fee.collector {
+                transfer_tokens(deps, env, info, collector, fee.amount)?;
             }
-            Ok(execute_send(
+            Ok(res
+                .add_attributes(fee.attributes())
+                .add_attribute("restriction_code", RESTRICTION_SUCCESS.to_string()))
+        }
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => {
+            let spender = normalize(deps.as_ref(), &spender)?;
+            ALLOWANCE_SPENDERS.save(deps.storage, (&spender, &info.sender), &true)?;
+            Ok(execute_increase_allowance(
                 deps,
                 env,
                 info,
-                contract.to_lowercase(),
+                spender.into_string(),
                 amount,
-                msg,
+                expires,
             )?)
         }
-        ExecuteMsg::IncreaseAllowance {
+        ExecuteMsg::Permit {
+            owner,
             spender,
             amount,
             expires,
-        } => Ok(execute_increase_allowance(
-            deps,
-            env,
-            info,
-            spender.to_lowercase(),
-            amount,
-            expires,
-        )?),
+            nonce,
+            signature,
+            pubkey,
+        } => permit(
+            deps, env, owner, spender, amount, expires, nonce, signature, pubkey,
+        ),
+        ExecuteMsg::ExecuteSigned {
+            payload,
+            signature,
+            pubkey,
+            nonce,
+        } => execute_signed(deps, env, info, payload, signature, pubkey, nonce),
         ExecuteMsg::DecreaseAllowance {
             spender,
             amount,
             expires,
-        } => Ok(execute_decrease_allowance(
-            deps,
-            env,
-            info,
-            spender.to_lowercase(),
-            amount,
-            expires,
-        )?),
+        } => {
+            let spender = normalize(deps.as_ref(), &spender)?;
+            let owner = info.sender.clone();
+            let res = execute_decrease_allowance(
+                deps.branch(),
+                env,
+                info,
+                spender.to_string(),
+                amount,
+                expires,
+            )?;
+            if !ALLOWANCES.has(deps.storage, (&owner, &spender)) {
+                ALLOWANCE_SPENDERS.remove(deps.storage, (&spender, &owner));
+            }
+            Ok(res)
+        }
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
             amount,
         } => {
-            if is_blocked(deps.as_ref(), owner.to_string()).unwrap_or_default() {
-                return Err(ContractError::Blocked {});
-            }
-            Ok(execute_transfer_from(
-                deps,
-                env,
-                info,
-                owner,
-                recipient.to_lowercase(),
+            let owner = normalize(deps.as_ref(), &owner)?;
+            let recipient = normalize(deps.as_ref(), &recipient)?;
+            let fee = check_transfer(deps.as_ref(), &env, &owner, &recipient, amount, false)?;
+            check_funds(deps.as_ref(), &env, &owner, Some(&info.sender), amount)?;
+            let amount = amount.checked_sub(fee.amount).map_err(StdError::from)?;
+
+            let res = transfer_tokens_from(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                &owner,
+                &recipient,
                 amount,
-            )?)
+            )?;
+            if let Some(collector) = &fee.collector {
+                transfer_tokens_from(deps, env, info, &owner, collector, fee.amount)?;
+            }
+            Ok(res
+                .add_attributes(fee.attributes())
+                .add_attribute("restriction_code", RESTRICTION_SUCCESS.to_string()))
         }
         ExecuteMsg::DestroyBlockedFunds { address } => {
-            if !is_blocked(deps.as_ref(), address.to_string()).unwrap_or_default() {
-                return Err(ContractError::NotBlocked {});
+            if approvals_required(deps.storage)? {
+                return Err(ContractError::ApprovalRequired {});
+            }
+
+            Ok(destroy_blocked_funds(deps, env, info, address)?)
+        }
+        ExecuteMsg::DestroyUnvestedFunds { address } => {
+            if approvals_required(deps.storage)? {
+                return Err(ContractError::ApprovalRequired {});
             }
 
-            Ok(destroy_blocked_funds(deps, info, address)?)
+            destroy_unvested_funds(deps, env, info, address)
         }
         ExecuteMsg::SendFrom {
             owner,
@@ -172,49 +427,243 @@ pub fn execute(
             amount,
             msg,
         } => {
-            if is_blocked(deps.as_ref(), owner.to_string()).unwrap_or_default() {
-                return Err(ContractError::Blocked {});
-            }
-            Ok(execute_send_from(
-                deps,
-                env,
-                info,
-                owner,
-                contract.to_lowercase(),
+            let owner = normalize(deps.as_ref(), &owner)?;
+            let contract = normalize(deps.as_ref(), &contract)?;
+            let fee = check_transfer(deps.as_ref(), &env, &owner, &contract, amount, true)?;
+            check_funds(deps.as_ref(), &env, &owner, Some(&info.sender), amount)?;
+            let amount = amount.checked_sub(fee.amount).map_err(StdError::from)?;
+
+            let res = send_tokens_from(
+                deps.branch(),
+                env.clone(),
+                info.clone(),
+                &owner,
+                &contract,
                 amount,
                 msg,
-            )?)
+            )?;
+            if let Some(collector) = &fee.collector {
+                transfer_tokens_from(deps, env, info, &owner, collector, fee.amount)?;
+            }
+            Ok(res
+                .add_attributes(fee.attributes())
+                .add_attribute("restriction_code", RESTRICTION_SUCCESS.to_string()))
+        }
+    }
+}
+
+/// Mirrors the current balances of `accounts` and the total supply into their snapshots
+/// at `height`, and carries the balance changes into the holder and blocked balance totals.
+/// Must run after every change to `BALANCES` or `TokenInfo::total_supply`; the wrappers
+/// below do so for the cw20-base calls that move tokens.
+pub fn sync_balances(storage: &mut dyn Storage, height: u64, accounts: &[Addr]) -> StdResult<()> {
+    for account in accounts {
+        sync_balance(storage, height, account)?;
+    }
+    sync_total_supply(storage, height)
+}
+
+fn sync_balance(storage: &mut dyn Storage, height: u64, account: &Addr) -> StdResult<()> {
+    let balance = BALANCES.may_load(storage, account)?.unwrap_or_default();
+    let previous = BALANCE_SNAPSHOTS
+        .may_load(storage, account)?
+        .unwrap_or_default();
+    if balance == previous {
+        return Ok(());
+    }
+    BALANCE_SNAPSHOTS.save(storage, account, &balance, height)?;
+
+    let blocked = BLOCKED.may_load(storage, account)?.unwrap_or_default();
+    update_stats(storage, |stats| {
+        if previous.is_zero() {
+            stats.holders += 1;
+        } else if balance.is_zero() {
+            stats.holders -= 1;
         }
+        if blocked {
+            stats.blocked_balance = (stats.blocked_balance + balance).checked_sub(previous)?;
+        }
+        Ok(())
+    })
+}
+
+fn sync_total_supply(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+    let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+    if TOTAL_SUPPLY_SNAPSHOTS
+        .may_load(storage)?
+        .unwrap_or_default()
+        != total_supply
+    {
+        TOTAL_SUPPLY_SNAPSHOTS.save(storage, &total_supply, height)?;
     }
+    Ok(())
+}
+
+/// Applies `action` to the balance of `address` and syncs it. Every balance change this
+/// contract makes itself goes through here.
+fn update_balance(
+    storage: &mut dyn Storage,
+    height: u64,
+    address: &Addr,
+    action: impl FnOnce(Uint128) -> StdResult<Uint128>,
+) -> StdResult<Uint128> {
+    let balance = action(BALANCES.may_load(storage, address)?.unwrap_or_default())?;
+    BALANCES.save(storage, address, &balance)?;
+    sync_balance(storage, height, address)?;
+    Ok(balance)
+}
+
+/// Applies `action` to the total supply and syncs its snapshot.
+fn update_total_supply(
+    storage: &mut dyn Storage,
+    height: u64,
+    action: impl FnOnce(Uint128) -> StdResult<Uint128>,
+) -> StdResult<()> {
+    TOKEN_INFO.update(storage, |mut meta| -> StdResult<_> {
+        meta.total_supply = action(meta.total_supply)?;
+        Ok(meta)
+    })?;
+    sync_total_supply(storage, height)
+}
+
+/// `execute_transfer` from cw20-base, syncing the sender and `recipient`.
+fn transfer_tokens(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    let accounts = [info.sender.clone(), recipient.clone()];
+    let res = execute_transfer(deps.branch(), env, info, recipient.to_string(), amount)?;
+    sync_balances(deps.storage, height, &accounts)?;
+    Ok(res)
+}
+
+/// `execute_send` from cw20-base, syncing the sender and `contract`.
+fn send_tokens(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: &Addr,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    let accounts = [info.sender.clone(), contract.clone()];
+    let res = execute_send(deps.branch(), env, info, contract.to_string(), amount, msg)?;
+    sync_balances(deps.storage, height, &accounts)?;
+    Ok(res)
+}
+
+/// `execute_transfer_from` from cw20-base, syncing `owner` and `recipient`.
+fn transfer_tokens_from(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    let res = execute_transfer_from(
+        deps.branch(),
+        env,
+        info,
+        owner.to_string(),
+        recipient.to_string(),
+        amount,
+    )?;
+    sync_balances(deps.storage, height, &[owner.clone(), recipient.clone()])?;
+    Ok(res)
+}
+
+/// `execute_send_from` from cw20-base, syncing `owner` and `contract`.
+fn send_tokens_from(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: &Addr,
+    contract: &Addr,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    let res = execute_send_from(
+        deps.branch(),
+        env,
+        info,
+        owner.to_string(),
+        contract.to_string(),
+        amount,
+        msg,
+    )?;
+    sync_balances(deps.storage, height, &[owner.clone(), contract.clone()])?;
+    Ok(res)
+}
+
+/// `execute_mint` from cw20-base, syncing `recipient`.
+fn mint_tokens(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    let res = execute_mint(deps.branch(), env, info, recipient.to_string(), amount)?;
+    sync_balance(deps.storage, height, recipient)?;
+    sync_total_supply(deps.storage, height)?;
+    Ok(res)
+}
+
+/// `execute_burn` from cw20-base, syncing the sender.
+fn burn_tokens(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    let account = info.sender.clone();
+    let res = execute_burn(deps.branch(), env, info, amount)?;
+    sync_balances(deps.storage, height, &[account])?;
+    Ok(res)
 }
 
 pub fn destroy_blocked_funds(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     address: String,
 ) -> Result<Response, ContractError> {
-    let address_to_check = deps.api.addr_validate(&address.to_lowercase())?;
+    let address_to_check = normalize(deps.as_ref(), &address)?;
+    if !is_blocked(deps.as_ref(), &address_to_check)? {
+        return Err(ContractError::NotBlocked {});
+    }
 
     let amount = BALANCES
         .may_load(deps.storage, &address_to_check)
         .unwrap_or_default();
 
     // lower balance
-    BALANCES.update(
+    update_balance(
         deps.storage,
+        env.block.height,
         &address_to_check,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance
-                .unwrap_or_default()
-                .checked_sub(amount.unwrap_or_default())?)
-        },
+        |balance| Ok(balance.checked_sub(amount.unwrap_or_default())?),
     )?;
 
     // reduce total_supply
-    TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
-        meta.total_supply = meta.total_supply.checked_sub(amount.unwrap_or_default())?;
-        Ok(meta)
+    update_total_supply(deps.storage, env.block.height, |total_supply| {
+        Ok(total_supply.checked_sub(amount.unwrap_or_default())?)
+    })?;
+    update_stats(deps.storage, |stats| {
+        stats.destroyed = stats.destroyed.checked_add(amount.unwrap_or_default())?;
+        Ok(())
     })?;
+    VESTING.remove(deps.storage, &address_to_check);
 
     let res = Response::new().add_attributes(vec![
         attr("action", "destroy_blocked_funds"),
@@ -225,348 +674,6688 @@ pub fn destroy_blocked_funds(
     Ok(res)
 }
 
-pub fn try_add_to_blocklist(
+/// Burns what a blocked address has not vested yet, or its whole balance if that is smaller,
+/// and ends its schedule.
+pub fn destroy_unvested_funds(
     deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     address: String,
 ) -> Result<Response, ContractError> {
-    let address_to_block = deps.api.addr_validate(&address.to_lowercase())?;
+    let address = normalize(deps.as_ref(), &address)?;
+    if !is_blocked(deps.as_ref(), &address)? {
+        return Err(ContractError::NotBlocked {});
+    }
+    let vesting = VESTING
+        .may_load(deps.storage, &address)?
+        .ok_or(ContractError::NotVesting {})?;
 
-    BLOCKED.save(deps.storage, &address_to_block, &true)?;
+    let (_, locked) = vesting_amounts(&vesting, env.block.time);
+    let balance = BALANCES
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    let amount = locked.min(balance);
+    update_balance(deps.storage, env.block.height, &address, |balance| {
+        Ok(balance - amount)
+    })?;
+    update_total_supply(deps.storage, env.block.height, |total_supply| {
+        Ok(total_supply.checked_sub(amount)?)
+    })?;
+    update_stats(deps.storage, |stats| {
+        stats.destroyed = stats.destroyed.checked_add(amount)?;
+        Ok(())
+    })?;
+    VESTING.remove(deps.storage, &address);
 
-    Ok(Response::new().add_attribute("blocked", "true"))
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "destroy_unvested_funds"),
+        attr("from", address),
+        attr("by", info.sender),
+        attr("amount", amount),
+    ]))
 }
 
-pub fn try_remove_from_blocklist(
-    deps: DepsMut,
-    address: String,
+/// Mints `amount` to `recipient` and locks it under `schedule`. Vesting starts out stopped if
+/// the recipient is blocked.
+pub fn mint_vested(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    schedule: VestingSchedule,
 ) -> Result<Response, ContractError> {
-    let address_to_unblock = deps.api.addr_validate(&address.to_lowercase())?;
+    let recipient = normalize(deps.as_ref(), &recipient)?;
+    if let VestingSchedule::Linear { start, end, cliff } = &schedule {
+        let cliff = cliff.unwrap_or(*start);
+        if start >= end || cliff < *start || cliff > *end {
+            return Err(ContractError::InvalidVestingSchedule {});
+        }
+    }
+    if let Some(vesting) = VESTING.may_load(deps.storage, &recipient)? {
+        if !vesting_amounts(&vesting, env.block.time).1.is_zero() {
+            return Err(ContractError::VestingExists {});
+        }
+    }
+    check_max_balance(deps.as_ref(), None, &recipient, amount)?;
+    check_reserves(deps.as_ref(), amount)?;
 
-    BLOCKED.save(deps.storage, &address_to_unblock, &false)?;
+    let res = mint_tokens(deps.branch(), env.clone(), info, &recipient, amount)?;
+    update_stats(deps.storage, |stats| {
+        stats.minted = stats.minted.checked_add(amount)?;
+        Ok(())
+    })?;
+    let paused_at = if is_blocked(deps.as_ref(), &recipient)? {
+        Some(env.block.time)
+    } else {
+        None
+    };
+    let vesting = Vesting {
+        amount,
+        schedule,
+        paused_for: 0,
+        paused_at,
+    };
+    let (_, locked) = vesting_amounts(&vesting, env.block.time);
+    VESTING.save(deps.storage, &recipient, &vesting)?;
 
-    Ok(Response::new().add_attribute("blocked", "false"))
+    Ok(res.add_attribute("locked", locked))
 }
 
-pub fn update_minter(
-    deps: DepsMut,
-    address: String,
-) -> Result<Response, ContractError> {
-    let new_minter = deps.api.addr_validate(&address.to_lowercase())?;
-
-    TOKEN_INFO.update(deps.storage, |mut state| -> Result<_, ContractError> {
-        state.mint = Some(MinterData {
-            minter: new_minter,
-            cap: None,
-        });
-        Ok(state)
-    })?;
-    Ok(Response::new().add_attribute("method", "update_minter"))
+/// Splits the amount minted under `vesting` into what has vested at `now` and what is still
+/// locked. The schedule runs on a clock that stands still while the holder is blocked.
+fn vesting_amounts(vesting: &Vesting, now: Timestamp) -> (Uint128, Uint128) {
+    let clock = vesting.paused_at.unwrap_or(now).seconds();
+    let elapsed = clock.saturating_sub(vesting.paused_for);
+    let vested = match &vesting.schedule {
+        VestingSchedule::Cliff { release_at } => {
+            if elapsed >= release_at.seconds() {
+                vesting.amount
+            } else {
+                Uint128::zero()
+            }
+        }
+        VestingSchedule::Linear { start, end, cliff } => {
+            let (start, end) = (start.seconds(), end.seconds());
+            if elapsed < cliff.unwrap_or_default().seconds().max(start) {
+                Uint128::zero()
+            } else if elapsed >= end {
+                vesting.amount
+            } else {
+                vesting.amount.multiply_ratio(elapsed - start, end - start)
+            }
+        }
+    };
+    (vested, vesting.amount - vested)
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::IsBlocked { address } => to_binary(&query_blocked(deps, address)?),
-        // inherited from cw20-base
-        QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
-        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
-        QueryMsg::AllAccounts { start_after, limit } => {
-            to_binary(&query_all_accounts(deps, start_after, limit)?)
+/// Stops the vesting clock of a newly blocked address.
+fn pause_vesting(storage: &mut dyn Storage, env: &Env, address: &Addr) -> StdResult<()> {
+    if let Some(mut vesting) = VESTING.may_load(storage, address)? {
+        if vesting.paused_at.is_none() {
+            vesting.paused_at = Some(env.block.time);
+            VESTING.save(storage, address, &vesting)?;
         }
-        QueryMsg::Allowance { owner, spender } => {
-            to_binary(&query_allowance(deps, owner, spender)?)
-        }
-        QueryMsg::AllAllowances {
-            owner,
-            start_after,
-            limit,
-        } => to_binary(&query_all_allowances(deps, owner, start_after, limit)?),
-        QueryMsg::Minter {} => to_binary(&query_minter(deps)?),
     }
+    Ok(())
 }
 
-fn is_blocked(deps: Deps, address: String) -> Option<bool> {
-    match deps.api.addr_validate(&address.to_lowercase()) {
-        Err(_) => Some(false),
-        Ok(addr) => BLOCKED.may_load(deps.storage, &addr).unwrap_or_default(),
+/// Restarts the vesting clock stopped by `pause_vesting`, leaving out the time spent blocked.
+fn resume_vesting(storage: &mut dyn Storage, env: &Env, address: &Addr) -> StdResult<()> {
+    if let Some(mut vesting) = VESTING.may_load(storage, address)? {
+        if let Some(paused_at) = vesting.paused_at.take() {
+            vesting.paused_for += env.block.time.seconds().saturating_sub(paused_at.seconds());
+            VESTING.save(storage, address, &vesting)?;
+        }
     }
+    Ok(())
 }
 
-fn query_blocked(deps: Deps, address: String) -> StdResult<BlockedResponse> {
-    Ok(BlockedResponse {
-        blocked: is_blocked(deps, address).unwrap_or_default(),
-    })
+/// Refuses to move `amount` out of `address` if that would reach into its locked tokens. A
+/// balance too small for `amount` altogether is left for the transfer itself to report.
+fn check_unlocked(
+    deps: Deps,
+    env: &Env,
+    address: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let vesting = match VESTING.may_load(deps.storage, address)? {
+        Some(vesting) => vesting,
+        None => return Ok(()),
+    };
+    let (_, locked) = vesting_amounts(&vesting, env.block.time);
+    let balance = BALANCES
+        .may_load(deps.storage, address)?
+        .unwrap_or_default();
+    if balance >= amount && balance - amount < locked {
+        return Err(ContractError::TokensLocked {});
+    }
+    Ok(())
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
-}
+/// Replaces a lost account: blocks `old`, moves its balance and the allowances it granted to
+/// `new`, and links the two in the block record of `old`. An allowance `new` already granted
+/// to the same spender is topped up and keeps its expiry.
+pub fn recover_account(
+    mut deps: DepsMut,
+    env: Env,
+    old: String,
+    new: String,
+    reference: String,
+) -> Result<Response, ContractError> {
+    if reference.trim().is_empty() || reference.len() > 512 {
+        return Err(ContractError::InvalidLegalReference {});
+    }
+    let old = normalize(deps.as_ref(), &old)?;
+    let new = normalize(deps.as_ref(), &new)?;
+    if old == new {
+        return Err(ContractError::InvalidAddress {
+            address: new.into_string(),
+        });
+    }
+    if is_blocked(deps.as_ref(), &new)? {
+        return Err(ContractError::Blocked {});
+    }
+    if let Some(BlockRecord {
+        recovered_to: Some(replacement),
+        ..
+    }) = BLOCK_RECORDS.may_load(deps.storage, &old)?
+    {
+        return Err(ContractError::AlreadyRecovered { replacement });
+    }
+    let balance = BALANCES.may_load(deps.storage, &old)?.unwrap_or_default();
+    check_max_balance(deps.as_ref(), Some(&old), &new, balance)?;
+    let vesting = VESTING.may_load(deps.storage, &old)?;
+    if vesting.is_some() {
+        if let Some(existing) = VESTING.may_load(deps.storage, &new)? {
+            if !vesting_amounts(&existing, env.block.time).1.is_zero() {
+                return Err(ContractError::VestingExists {});
+            }
+        }
+    }
 
-#[cfg(test)]
-mod tests {
-    use cosmwasm_std::from_binary;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cw20::TokenInfoResponse;
+    let blocked = try_add_to_blocklist(deps.branch(), env.clone(), old.to_string())?;
+    let mut record = BLOCK_RECORDS
+        .may_load(deps.storage, &old)?
+        .unwrap_or(BlockRecord {
+            blocked_at: env.block.time,
+            height: env.block.height,
+            recovered_to: None,
+            recovery_reference: None,
+            origin: None,
+        });
+    record.recovered_to = Some(new.to_string());
+    record.recovery_reference = Some(reference.clone());
+    BLOCK_RECORDS.save(deps.storage, &old, &record)?;
 
-    use super::*;
-    fn get_balance<T: Into<String>>(deps: Deps, address: T) -> Uint128 {
-        query_balance(deps, address.into()).unwrap().balance
+    BALANCES.remove(deps.storage, &old);
+    sync_balance(deps.storage, env.block.height, &old)?;
+    update_balance(deps.storage, env.block.height, &new, |current| {
+        Ok(current.checked_add(balance)?)
+    })?;
+    if let Some(vesting) = vesting {
+        VESTING.remove(deps.storage, &old);
+        VESTING.save(deps.storage, &new, &vesting)?;
+        resume_vesting(deps.storage, &env, &new)?;
     }
 
-    // this will set up the instantiation for other tests
-    fn do_instantiate(mut deps: DepsMut) -> TokenInfoResponse {
-        let instantiate_msg = InstantiateMsg {
-            name: "Auto Gen".to_string(),
-            symbol: "AUTO".to_string(),
-            decimals: 6,
-        };
-        let info = mock_info("creator", &[]);
-        let env = mock_env();
-        let res = instantiate(deps.branch(), env, info, instantiate_msg).unwrap();
-        assert_eq!(0, res.messages.len());
+    let granted = ALLOWANCES
+        .prefix(&old)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (spender, allowance) in &granted {
+        ALLOWANCES.remove(deps.storage, (&old, spender));
+        ALLOWANCE_SPENDERS.remove(deps.storage, (spender, &old));
+        if *spender == new {
+            continue;
+        }
+        ALLOWANCES.update(deps.storage, (&new, spender), |current| -> StdResult<_> {
+            Ok(match current {
+                Some(mut current) => {
+                    current.allowance = current.allowance.checked_add(allowance.allowance)?;
+                    current
+                }
+                None => allowance.clone(),
+            })
+        })?;
+        ALLOWANCE_SPENDERS.save(deps.storage, (spender, &new), &true)?;
+    }
 
-        let meta = query_token_info(deps.as_ref()).unwrap();
-        assert_eq!(
-            meta,
-            TokenInfoResponse {
-                name: "Auto Gen".to_string(),
+    Ok(Response::new()
+        .add_submessages(blocked.messages)
+        .add_attributes(vec![
+            attr("method", "recover_account"),
+            attr("old", old),
+            attr("new", new),
+            attr("amount", balance),
+            attr("allowances", granted.len().to_string()),
+            attr("reference", reference),
+        ]))
+}
+
+/// Escrows `amount` from the sender for `to`. Neither side may be blocked, and the tokens must
+/// be free to leave the sender's account.
+pub fn place_hold(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+    amount: Uint128,
+    expires: Expiration,
+) -> Result<Response, ContractError> {
+    let sender = normalize(deps.as_ref(), info.sender.as_str())?;
+    let recipient = normalize(deps.as_ref(), &to)?;
+    if is_blocked(deps.as_ref(), &sender)? || is_blocked(deps.as_ref(), &recipient)? {
+        return Err(ContractError::Blocked {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    check_unlocked(deps.as_ref(), &env, &sender, amount)?;
+    check_funds(deps.as_ref(), &env, &sender, None, amount)?;
+    check_max_balance(deps.as_ref(), Some(&sender), &recipient, amount)?;
+
+    let escrow = env.contract.address.clone();
+    transfer_tokens(deps.branch(), env.clone(), info, &escrow, amount)?;
+
+    let id = NEXT_HOLD_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_HOLD_ID.save(deps.storage, &(id + 1))?;
+    let hold = Hold {
+        sender: sender.clone(),
+        recipient: recipient.clone(),
+        amount,
+        expires,
+        status: HoldStatus::Pending,
+        created_at: env.block.time,
+        resolved_by: None,
+        resolved_at: None,
+    };
+    holds().save(deps.storage, id, &hold)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "hold"),
+        attr("hold_id", id.to_string()),
+        attr("from", sender),
+        attr("to", recipient),
+        attr("amount", amount),
+        attr("expires", expires.to_string()),
+    ]))
+}
+
+fn pending_hold(deps: Deps, id: u64) -> Result<Hold, ContractError> {
+    let hold = holds()
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::HoldNotFound { id })?;
+    if hold.status != HoldStatus::Pending {
+        return Err(ContractError::HoldClosed { id });
+    }
+    Ok(hold)
+}
+
+/// Pays a pending hold out to its recipient, less the transfer fee. Both sides are checked
+/// against the blocklist again, as either may have been blocked while the tokens were held.
+/// The maximum balance applies to the fee collector as well as the recipient.
+pub fn release_hold(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut hold = pending_hold(deps.as_ref(), id)?;
+    if !hold.expires.is_expired(&env.block)
+        && !has_role(deps.storage, Role::Compliance, &info.sender)?
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if is_blocked(deps.as_ref(), &hold.sender)? || is_blocked(deps.as_ref(), &hold.recipient)? {
+        return Err(ContractError::Blocked {});
+    }
+    let fee = transfer_fee(deps.as_ref(), &hold.sender, &hold.recipient, hold.amount)?;
+    let amount = hold.amount - fee.amount;
+    check_max_balance(deps.as_ref(), Some(&hold.sender), &hold.recipient, amount)?;
+    if let Some(collector) = &fee.collector {
+        check_max_balance(deps.as_ref(), Some(&hold.sender), collector, fee.amount)?;
+    }
+
+    let escrow = MessageInfo {
+        sender: env.contract.address.clone(),
+        funds: vec![],
+    };
+    // A minimum fee can take the whole hold, leaving nothing for the recipient.
+    if !amount.is_zero() {
+        transfer_tokens(
+            deps.branch(),
+            env.clone(),
+            escrow.clone(),
+            &hold.recipient,
+            amount,
+        )?;
+    }
+    if let Some(collector) = &fee.collector {
+        transfer_tokens(deps.branch(), env.clone(), escrow, collector, fee.amount)?;
+    }
+
+    hold.status = HoldStatus::Released;
+    hold.resolved_by = Some(info.sender);
+    hold.resolved_at = Some(env.block.time);
+    holds().save(deps.storage, id, &hold)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "release"),
+            attr("hold_id", id.to_string()),
+            attr("from", hold.sender),
+            attr("to", hold.recipient),
+            attr("amount", amount),
+        ])
+        .add_attributes(fee.attributes()))
+}
+
+/// Returns a pending hold to its sender, even one blocked since, whose tokens then stay frozen.
+pub fn revert_hold(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, Role::Compliance, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut hold = pending_hold(deps.as_ref(), id)?;
+
+    let escrow = MessageInfo {
+        sender: env.contract.address.clone(),
+        funds: vec![],
+    };
+    transfer_tokens(
+        deps.branch(),
+        env.clone(),
+        escrow,
+        &hold.sender,
+        hold.amount,
+    )?;
+
+    hold.status = HoldStatus::Reverted;
+    hold.resolved_by = Some(info.sender);
+    hold.resolved_at = Some(env.block.time);
+    holds().save(deps.storage, id, &hold)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "revert"),
+        attr("hold_id", id.to_string()),
+        attr("from", hold.sender),
+        attr("to", hold.recipient),
+        attr("amount", hold.amount),
+    ]))
+}
+
+/// Sets an allowance on the strength of the owner's signature. Neither the owner nor the
+/// spender may be blocked.
+#[allow(clippy::too_many_arguments)]
+pub fn permit(
+    deps: DepsMut,
+    env: Env,
+    owner: String,
+    spender: String,
+    amount: Uint128,
+    expires: Expiration,
+    nonce: u64,
+    signature: Binary,
+    pubkey: Binary,
+) -> Result<Response, ContractError> {
+    let owner = normalize(deps.as_ref(), &owner)?;
+    let spender = normalize(deps.as_ref(), &spender)?;
+    if owner == spender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    if is_blocked(deps.as_ref(), &owner)? || is_blocked(deps.as_ref(), &spender)? {
+        return Err(ContractError::Blocked {});
+    }
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+
+    let payload = PermitPayload {
+        chain_id: env.block.chain_id,
+        contract: env.contract.address.into_string(),
+        owner: owner.to_string(),
+        spender: spender.to_string(),
+        amount,
+        expires,
+        nonce,
+    };
+    verify_signature(
+        deps.as_ref(),
+        &owner,
+        &to_vec(&payload)?,
+        &signature,
+        &pubkey,
+    )?;
+    use_nonce(deps.storage, &owner, nonce)?;
+
+    if amount.is_zero() {
+        ALLOWANCES.remove(deps.storage, (&owner, &spender));
+        ALLOWANCE_SPENDERS.remove(deps.storage, (&spender, &owner));
+    } else {
+        let allowance = AllowanceResponse {
+            allowance: amount,
+            expires,
+        };
+        ALLOWANCES.save(deps.storage, (&owner, &spender), &allowance)?;
+        ALLOWANCE_SPENDERS.save(deps.storage, (&spender, &owner), &true)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "permit"),
+        attr("owner", owner),
+        attr("spender", spender),
+        attr("amount", amount),
+        attr("nonce", nonce.to_string()),
+    ]))
+}
+
+/// Runs a signed `Transfer` or `Send` as if the signer had sent it, so the blocklist and every
+/// other transfer policy apply to the signer unchanged. The relayer fee is a second transfer
+/// from the signer to the account submitting the message.
+pub fn execute_signed(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payload: SignedPayload,
+    signature: Binary,
+    pubkey: Binary,
+    nonce: u64,
+) -> Result<Response, ContractError> {
+    let signer = normalize(deps.as_ref(), &payload.signer)?;
+    if payload.expires.is_expired(&env.block) {
+        return Err(ContractError::SignatureExpired {});
+    }
+    let document = SignedDocument {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        payload: payload.clone(),
+        nonce,
+    };
+    verify_signature(
+        deps.as_ref(),
+        &signer,
+        &to_vec(&document)?,
+        &signature,
+        &pubkey,
+    )?;
+    use_nonce(deps.storage, &signer, nonce)?;
+
+    let on_behalf = MessageInfo {
+        sender: signer.clone(),
+        funds: vec![],
+    };
+    let msg = match payload.msg {
+        SignedMsg::Transfer { recipient, amount } => ExecuteMsg::Transfer { recipient, amount },
+        SignedMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        },
+    };
+    let mut res = dispatch(deps.branch(), env.clone(), on_behalf.clone(), msg)?;
+    if let Some(fee) = payload.relayer_fee.filter(|fee| !fee.is_zero()) {
+        let msg = ExecuteMsg::Transfer {
+            recipient: info.sender.to_string(),
+            amount: fee,
+        };
+        dispatch(deps, env, on_behalf, msg)?;
+        res = res.add_attribute("relayer_fee", fee);
+    }
+
+    Ok(res.add_attributes(vec![
+        attr("signer", signer),
+        attr("relayer", info.sender),
+        attr("nonce", nonce.to_string()),
+    ]))
+}
+
+/// Records a reserve attestation from the attestor role.
+pub fn submit_attestation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reserves: Uint128,
+    as_of: Timestamp,
+    report_uri: String,
+    hash: String,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, Role::Attestor, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let invalid = |reason: &str| ContractError::InvalidAttestation {
+        reason: reason.to_string(),
+    };
+    if report_uri.trim().is_empty() || report_uri.len() > 512 {
+        return Err(invalid("report URI must be 1 to 512 characters"));
+    }
+    if hash.trim().is_empty() || hash.len() > 128 {
+        return Err(invalid("hash must be 1 to 128 characters"));
+    }
+    if as_of > env.block.time {
+        return Err(invalid("as_of is in the future"));
+    }
+    if let Some((_, latest)) = latest_attestation(deps.storage)? {
+        if as_of <= latest.as_of {
+            return Err(invalid("as_of must be later than the latest attestation"));
+        }
+    }
+
+    let id = NEXT_ATTESTATION_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NEXT_ATTESTATION_ID.save(deps.storage, &(id + 1))?;
+    let attestation = Attestation {
+        reserves,
+        as_of,
+        report_uri,
+        hash,
+        submitted_by: info.sender,
+        submitted_at: env.block.time,
+    };
+    ATTESTATIONS.save(deps.storage, id, &attestation)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "submit_attestation"),
+        attr("attestation_id", id.to_string()),
+        attr("reserves", reserves),
+        attr("as_of", as_of.seconds().to_string()),
+        attr("hash", attestation.hash),
+    ]))
+}
+
+pub fn update_reserve_enforcement(deps: DepsMut, enabled: bool) -> StdResult<Response> {
+    ENFORCE_RESERVES.save(deps.storage, &enabled)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_reserve_enforcement"),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+fn latest_attestation(storage: &dyn Storage) -> StdResult<Option<(u64, Attestation)>> {
+    ATTESTATIONS
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+}
+
+/// While enforcement is on, minting `amount` must keep the total supply within the latest
+/// attested reserves. Without an attestation nothing can be minted.
+fn check_reserves(deps: Deps, amount: Uint128) -> Result<(), ContractError> {
+    if !ENFORCE_RESERVES.may_load(deps.storage)?.unwrap_or_default() {
+        return Ok(());
+    }
+    let reserves = latest_attestation(deps.storage)?
+        .map(|(_, attestation)| attestation.reserves)
+        .unwrap_or_default();
+    let total_supply = TOKEN_INFO.load(deps.storage)?.total_supply;
+    if total_supply.checked_add(amount).map_err(StdError::from)? > reserves {
+        return Err(ContractError::ReservesExceeded {});
+    }
+    Ok(())
+}
+
+/// Mints through cw20-base, which only lets the minter mint, for flows where the contract
+/// mints on the minter's behalf. The mint cap applies.
+fn mint_on_behalf_of_minter(
+    deps: DepsMut,
+    env: Env,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let minter = TOKEN_INFO
+        .load(deps.storage)?
+        .mint
+        .ok_or(ContractError::Unauthorized {})?
+        .minter;
+    let minter_info = MessageInfo {
+        sender: minter,
+        funds: vec![],
+    };
+    mint_tokens(deps, env, minter_info, recipient, amount)
+}
+
+/// Opens a mint or redeem request for the sender. Redeem requests move the tokens into the
+/// contract's custody.
+pub fn open_request(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    kind: RequestKind,
+    amount: Uint128,
+    reference: String,
+) -> Result<Response, ContractError> {
+    let requester = normalize(deps.as_ref(), info.sender.as_str())?;
+    if is_blocked(deps.as_ref(), &requester)? {
+        return Err(ContractError::Blocked {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if reference.trim().is_empty() || reference.len() > 256 {
+        return Err(ContractError::InvalidReference {});
+    }
+    if kind == RequestKind::Redeem {
+        check_unlocked(deps.as_ref(), &env, &requester, amount)?;
+        check_funds(deps.as_ref(), &env, &requester, None, amount)?;
+        let escrow = env.contract.address.clone();
+        transfer_tokens(deps.branch(), env.clone(), info, &escrow, amount)?;
+    }
+
+    let id = NEXT_REQUEST_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_REQUEST_ID.save(deps.storage, &(id + 1))?;
+    let request = IssuanceRequest {
+        kind,
+        requester: requester.clone(),
+        amount,
+        reference,
+        status: RequestStatus::Pending,
+        created_at: env.block.time,
+        resolved_by: None,
+        resolved_at: None,
+        reason: None,
+    };
+    issuance_requests().save(deps.storage, id, &request)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "open_request"),
+        attr("kind", request_kind_str(kind)),
+        attr("request_id", id.to_string()),
+        attr("requester", requester),
+        attr("amount", amount),
+    ]))
+}
+
+fn request_kind_str(kind: RequestKind) -> &'static str {
+    match kind {
+        RequestKind::Mint => "mint",
+        RequestKind::Redeem => "redeem",
+    }
+}
+
+/// Loads a pending request for the operator role to settle or reject.
+fn pending_request(
+    deps: Deps,
+    info: &MessageInfo,
+    id: u64,
+) -> Result<IssuanceRequest, ContractError> {
+    if !has_role(deps.storage, Role::Operator, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let request = issuance_requests()
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::RequestNotFound { id })?;
+    if request.status != RequestStatus::Pending {
+        return Err(ContractError::RequestClosed { id });
+    }
+    Ok(request)
+}
+
+/// Mints the tokens of a mint request, or burns the escrowed tokens of a redeem request. A
+/// requester blocked since opening the request can't be paid; such requests can be rejected.
+pub fn fulfil_request(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut request = pending_request(deps.as_ref(), &info, id)?;
+    if is_blocked(deps.as_ref(), &request.requester)? {
+        return Err(ContractError::Blocked {});
+    }
+
+    match request.kind {
+        RequestKind::Mint => {
+            check_max_balance(deps.as_ref(), None, &request.requester, request.amount)?;
+            check_reserves(deps.as_ref(), request.amount)?;
+            mint_on_behalf_of_minter(
+                deps.branch(),
+                env.clone(),
+                &request.requester,
+                request.amount,
+            )?;
+            update_stats(deps.storage, |stats| {
+                stats.minted = stats.minted.checked_add(request.amount)?;
+                Ok(())
+            })?;
+        }
+        RequestKind::Redeem => {
+            let escrow = MessageInfo {
+                sender: env.contract.address.clone(),
+                funds: vec![],
+            };
+            burn_tokens(deps.branch(), env.clone(), escrow, request.amount)?;
+            update_stats(deps.storage, |stats| {
+                stats.redeemed = stats.redeemed.checked_add(request.amount)?;
+                Ok(())
+            })?;
+        }
+    }
+
+    request.status = RequestStatus::Fulfilled;
+    request.resolved_by = Some(info.sender);
+    request.resolved_at = Some(env.block.time);
+    issuance_requests().save(deps.storage, id, &request)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "fulfil_request"),
+        attr("kind", request_kind_str(request.kind)),
+        attr("request_id", id.to_string()),
+        attr("requester", request.requester),
+        attr("amount", request.amount),
+    ]))
+}
+
+/// Closes a pending request. The escrow of a redeem request goes back to the requester.
+pub fn reject_request(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let mut request = pending_request(deps.as_ref(), &info, id)?;
+
+    if request.kind == RequestKind::Redeem {
+        let escrow = MessageInfo {
+            sender: env.contract.address.clone(),
+            funds: vec![],
+        };
+        transfer_tokens(
+            deps.branch(),
+            env.clone(),
+            escrow,
+            &request.requester,
+            request.amount,
+        )?;
+    }
+
+    request.status = RequestStatus::Rejected;
+    request.resolved_by = Some(info.sender);
+    request.resolved_at = Some(env.block.time);
+    request.reason = Some(reason);
+    issuance_requests().save(deps.storage, id, &request)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "reject_request"),
+        attr("kind", request_kind_str(request.kind)),
+        attr("request_id", id.to_string()),
+        attr("requester", request.requester),
+    ]))
+}
+
+pub fn update_wrap_denom(deps: DepsMut, denom: Option<String>) -> Result<Response, ContractError> {
+    let current = WRAP_DENOM.may_load(deps.storage)?;
+    let wrapped = WRAPPED.may_load(deps.storage)?.unwrap_or_default();
+    if current != denom && !wrapped.is_zero() {
+        return Err(ContractError::ReservesOutstanding {});
+    }
+
+    match &denom {
+        Some(denom) if denom.is_empty() => {
+            return Err(StdError::generic_err("Denom cannot be empty").into())
+        }
+        Some(denom) => WRAP_DENOM.save(deps.storage, denom)?,
+        None => WRAP_DENOM.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_wrap_denom"),
+        attr("denom", denom.unwrap_or_else(|| "none".to_string())),
+    ]))
+}
+
+/// Mints tokens to the sender for the wrapped denom they sent. The mint cap and maximum balance
+/// apply as for `Mint`.
+pub fn wrap(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let denom = WRAP_DENOM
+        .may_load(deps.storage)?
+        .ok_or(ContractError::WrapDisabled {})?;
+    let amount = must_pay(&info, &denom)?;
+    let sender = normalize(deps.as_ref(), info.sender.as_str())?;
+    if is_blocked(deps.as_ref(), &sender)? {
+        return Err(ContractError::Blocked {});
+    }
+    check_max_balance(deps.as_ref(), None, &sender, amount)?;
+
+    mint_on_behalf_of_minter(deps.branch(), env, &sender, amount)?;
+    let wrapped = WRAPPED.may_load(deps.storage)?.unwrap_or_default();
+    WRAPPED.save(
+        deps.storage,
+        &wrapped.checked_add(amount).map_err(StdError::from)?,
+    )?;
+    update_stats(deps.storage, |stats| {
+        stats.minted = stats.minted.checked_add(amount)?;
+        Ok(())
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "wrap"),
+        attr("to", sender),
+        attr("amount", amount),
+        attr("denom", denom),
+    ]))
+}
+
+/// Burns `amount` of the sender's tokens and sends them the same amount of the wrapped denom.
+pub fn unwrap(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let denom = WRAP_DENOM
+        .may_load(deps.storage)?
+        .ok_or(ContractError::WrapDisabled {})?;
+    let sender = normalize(deps.as_ref(), info.sender.as_str())?;
+    if is_blocked(deps.as_ref(), &sender)? {
+        return Err(ContractError::Blocked {});
+    }
+    let wrapped = WRAPPED.may_load(deps.storage)?.unwrap_or_default();
+    if amount > wrapped {
+        return Err(ContractError::InsufficientReserves {});
+    }
+    check_unlocked(deps.as_ref(), &env, &sender, amount)?;
+
+    burn_tokens(deps.branch(), env, info, amount)?;
+    WRAPPED.save(deps.storage, &(wrapped - amount))?;
+    update_stats(deps.storage, |stats| {
+        stats.redeemed = stats.redeemed.checked_add(amount)?;
+        Ok(())
+    })?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: coins(amount.u128(), &denom),
+        })
+        .add_attributes(vec![
+            attr("method", "unwrap"),
+            attr("from", sender),
+            attr("amount", amount),
+            attr("denom", denom),
+        ]))
+}
+
+/// Moves `amount` from `from` to `to` on behalf of the controller role, bypassing the
+/// blocklist. The maximum balance of `to` is still enforced.
+pub fn force_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from: String,
+    to: String,
+    amount: Uint128,
+    legal_reference: String,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, Role::Controller, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    if legal_reference.trim().is_empty() || legal_reference.len() > 512 {
+        return Err(ContractError::InvalidLegalReference {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let from = normalize(deps.as_ref(), &from)?;
+    let to = normalize(deps.as_ref(), &to)?;
+    check_max_balance(deps.as_ref(), Some(&from), &to, amount)?;
+    // A court order reaches the tokens `from` can spend, not the ones still vesting.
+    check_unlocked(deps.as_ref(), &env, &from, amount)?;
+
+    update_balance(deps.storage, env.block.height, &from, |balance| {
+        Ok(balance.checked_sub(amount)?)
+    })?;
+    update_balance(deps.storage, env.block.height, &to, |balance| {
+        Ok(balance.checked_add(amount)?)
+    })?;
+
+    let event = Event::new("controller_transfer").add_attributes(vec![
+        attr("from", from),
+        attr("to", to),
+        attr("amount", amount),
+        attr("by", info.sender),
+        attr("legal_reference", legal_reference),
+    ]);
+    Ok(Response::new()
+        .add_attribute("action", "force_transfer")
+        .add_event(event))
+}
+
+fn update_stats(
+    storage: &mut dyn Storage,
+    action: impl FnOnce(&mut Stats) -> StdResult<()>,
+) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    action(&mut stats)?;
+    STATS.save(storage, &stats)
+}
+
+/// Recomputes the holder and blocklist totals from scratch. Totals that can't be recovered
+/// from current state, such as amounts minted or destroyed, are kept.
+fn rebuild_stats(storage: &mut dyn Storage) -> StdResult<()> {
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.holders = 0;
+    stats.blocked_addresses = 0;
+    stats.blocked_balance = Uint128::zero();
+
+    let balances = BALANCES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (_, balance) in &balances {
+        if !balance.is_zero() {
+            stats.holders += 1;
+        }
+    }
+    for item in BLOCKED.range(storage, None, None, Order::Ascending) {
+        let (address, blocked) = item?;
+        if blocked {
+            stats.blocked_addresses += 1;
+            stats.blocked_balance += BALANCES.may_load(storage, &address)?.unwrap_or_default();
+        }
+    }
+    STATS.save(storage, &stats)
+}
+
+pub fn try_add_to_blocklist(
+    deps: DepsMut,
+    env: Env,
+    address: String,
+) -> Result<Response, ContractError> {
+    let address_to_block = normalize(deps.as_ref(), &address)?;
+
+    let mut res = Response::new().add_attribute("blocked", "true");
+    if block_address(deps.storage, &env, &address_to_block, None)? {
+        res = res.add_messages(blocklist_update_msgs(
+            deps.as_ref(),
+            &env,
+            &address_to_block,
+            true,
+        )?);
+    }
+    Ok(res)
+}
+
+pub fn try_remove_from_blocklist(
+    deps: DepsMut,
+    env: Env,
+    address: String,
+) -> Result<Response, ContractError> {
+    let address_to_unblock = normalize(deps.as_ref(), &address)?;
+
+    let mut res = Response::new().add_attribute("blocked", "false");
+    if unblock_address(deps.storage, &env, &address_to_unblock)? {
+        res = res.add_messages(blocklist_update_msgs(
+            deps.as_ref(),
+            &env,
+            &address_to_unblock,
+            false,
+        )?);
+    }
+    Ok(res)
+}
+
+/// Puts `address` on the blocklist and records when. `origin` is set for blocks received over
+/// IBC, and a local block of an address blocked remotely takes it over. Returns whether the
+/// blocklist changed in a way counterpart contracts should hear about.
+pub(crate) fn block_address(
+    storage: &mut dyn Storage,
+    env: &Env,
+    address: &Addr,
+    origin: Option<RemoteOrigin>,
+) -> StdResult<bool> {
+    if BLOCKED.may_load(storage, address)?.unwrap_or_default() {
+        if let (None, Some(mut record)) = (&origin, BLOCK_RECORDS.may_load(storage, address)?) {
+            if record.origin.take().is_some() {
+                BLOCK_RECORDS.save(storage, address, &record)?;
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+
+    // A recovered address keeps the link to its replacement when it is blocked again.
+    let previous = BLOCK_RECORDS.may_load(storage, address)?;
+    let record = BlockRecord {
+        blocked_at: env.block.time,
+        height: env.block.height,
+        recovered_to: previous
+            .as_ref()
+            .and_then(|record| record.recovered_to.clone()),
+        recovery_reference: previous.and_then(|record| record.recovery_reference),
+        origin,
+    };
+    BLOCK_RECORDS.save(storage, address, &record)?;
+    let balance = BALANCES.may_load(storage, address)?.unwrap_or_default();
+    update_stats(storage, |stats| {
+        stats.blocked_addresses += 1;
+        stats.blocked_balance = stats.blocked_balance.checked_add(balance)?;
+        Ok(())
+    })?;
+    BLOCKED.save(storage, address, &true)?;
+    pause_vesting(storage, env, address)?;
+    Ok(true)
+}
+
+/// Takes `address` off the blocklist. Returns whether it was blocked.
+pub(crate) fn unblock_address(
+    storage: &mut dyn Storage,
+    env: &Env,
+    address: &Addr,
+) -> StdResult<bool> {
+    let blocked = BLOCKED.may_load(storage, address)?.unwrap_or_default();
+    if blocked {
+        resume_vesting(storage, env, address)?;
+        let balance = BALANCES.may_load(storage, address)?.unwrap_or_default();
+        update_stats(storage, |stats| {
+            stats.blocked_addresses -= 1;
+            stats.blocked_balance = stats.blocked_balance.checked_sub(balance)?;
+            Ok(())
+        })?;
+    }
+    // The record of a recovered address is kept so the link to its replacement survives.
+    match BLOCK_RECORDS.may_load(storage, address)? {
+        Some(mut record) if record.recovered_to.is_some() => {
+            record.origin = None;
+            BLOCK_RECORDS.save(storage, address, &record)?;
+        }
+        _ => BLOCK_RECORDS.remove(storage, address),
+    }
+    BLOCKED.save(storage, address, &false)?;
+    Ok(blocked)
+}
+
+pub fn update_minter(
+    deps: DepsMut,
+    address: String,
+) -> Result<Response, ContractError> {
+    let new_minter = normalize(deps.as_ref(), &address)?;
+
+    TOKEN_INFO.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        // The cap has its own timelocked action and stays with the new minter.
+        state.mint = Some(MinterData {
+            minter: new_minter,
+            cap: state.mint.and_then(|mint| mint.cap),
+        });
+        Ok(state)
+    })?;
+    Ok(Response::new().add_attribute("method", "update_minter"))
+}
+
+pub fn update_mint_cap(deps: DepsMut, cap: Option<Uint128>) -> Result<Response, ContractError> {
+    TOKEN_INFO.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        let mint = state.mint.as_mut().ok_or(ContractError::Unauthorized {})?;
+        mint.cap = cap;
+        Ok(state)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_mint_cap"),
+        attr("cap", cap.map(|cap| cap.to_string()).unwrap_or_default()),
+    ]))
+}
+
+pub fn update_timelock(
+    deps: DepsMut,
+    delay: u64,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    let guardian = guardian
+        .map(|guardian| normalize(deps.as_ref(), &guardian))
+        .transpose()?;
+    TIMELOCK.save(deps.storage, &TimelockConfig { delay, guardian })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_timelock"),
+        attr("delay", delay.to_string()),
+    ]))
+}
+
+fn timelock_delay(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(TIMELOCK
+        .may_load(storage)?
+        .map(|config| config.delay)
+        .unwrap_or_default())
+}
+
+/// Returns whether `action` must go through the timelock. Lowering the mint cap only restricts
+/// the minter, so only raising or removing it counts.
+fn requires_timelock(deps: Deps, action: &TimelockAction) -> StdResult<bool> {
+    Ok(match action {
+        TimelockAction::UpdateMintCap { cap } => {
+            match (TOKEN_INFO.load(deps.storage)?.get_cap(), cap) {
+                (Some(current), Some(cap)) => *cap > current,
+                (Some(_), None) => true,
+                (None, _) => false,
+            }
+        }
+        _ => true,
+    })
+}
+
+/// Runs an admin action sent directly, refusing sensitive ones while a timelock is configured.
+fn run_admin_action(
+    deps: DepsMut,
+    env: Env,
+    action: TimelockAction,
+) -> Result<Response, ContractError> {
+    if timelock_delay(deps.storage)? > 0 && requires_timelock(deps.as_ref(), &action)? {
+        return Err(ContractError::TimelockRequired {});
+    }
+    apply_admin_action(deps, env, action)
+}
+
+fn apply_admin_action(
+    deps: DepsMut,
+    env: Env,
+    action: TimelockAction,
+) -> Result<Response, ContractError> {
+    match action {
+        TimelockAction::RemoveFromBlockedList { address } => {
+            try_remove_from_blocklist(deps, env, address)
+        }
+        TimelockAction::UpdateMinter { address } => update_minter(deps, address),
+        TimelockAction::UpdateMintCap { cap } => update_mint_cap(deps, cap),
+        TimelockAction::UpdateTimelock { delay, guardian } => {
+            update_timelock(deps, delay, guardian)
+        }
+    }
+}
+
+/// Normalizes the addresses in `action` so bad input is caught when scheduling rather than
+/// once the delay has passed.
+fn normalize_action(deps: Deps, action: TimelockAction) -> Result<TimelockAction, ContractError> {
+    Ok(match action {
+        TimelockAction::RemoveFromBlockedList { address } => {
+            TimelockAction::RemoveFromBlockedList {
+                address: normalize(deps, &address)?.into_string(),
+            }
+        }
+        TimelockAction::UpdateMinter { address } => TimelockAction::UpdateMinter {
+            address: normalize(deps, &address)?.into_string(),
+        },
+        TimelockAction::UpdateTimelock { delay, guardian } => TimelockAction::UpdateTimelock {
+            delay,
+            guardian: guardian
+                .map(|guardian| normalize(deps, &guardian).map(Addr::into_string))
+                .transpose()?,
+        },
+        action => action,
+    })
+}
+
+pub fn schedule_action(
+    deps: DepsMut,
+    env: Env,
+    action: TimelockAction,
+    eta: Timestamp,
+) -> Result<Response, ContractError> {
+    if matches!(action, TimelockAction::UpdateMinter { .. }) && approvals_required(deps.storage)? {
+        return Err(ContractError::ApprovalRequired {});
+    }
+    let action = normalize_action(deps.as_ref(), action)?;
+    let earliest = env.block.time.plus_seconds(timelock_delay(deps.storage)?);
+    if eta < earliest {
+        return Err(ContractError::EtaTooEarly { earliest });
+    }
+
+    enqueue_action(deps.storage, action, eta, false)
+}
+
+/// Queues `action` until `eta`. `approved` records that an approved proposal queued it.
+fn enqueue_action(
+    storage: &mut dyn Storage,
+    action: TimelockAction,
+    eta: Timestamp,
+    approved: bool,
+) -> Result<Response, ContractError> {
+    let id = NEXT_ACTION_ID.may_load(storage)?.unwrap_or_default();
+    NEXT_ACTION_ID.save(storage, &(id + 1))?;
+    let scheduled = ScheduledAction {
+        action,
+        eta,
+        approved,
+    };
+    SCHEDULED_ACTIONS.save(storage, id, &scheduled)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "schedule"),
+        attr("id", id.to_string()),
+        attr("eta", eta.seconds().to_string()),
+    ]))
+}
+
+pub fn execute_scheduled(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let scheduled = SCHEDULED_ACTIONS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::ActionNotFound { id })?;
+    if env.block.time < scheduled.eta {
+        return Err(ContractError::ActionNotReady {
+            id,
+            eta: scheduled.eta,
+        });
+    }
+    // A minter change queued before approvers were configured still needs their approval.
+    if matches!(scheduled.action, TimelockAction::UpdateMinter { .. })
+        && !scheduled.approved
+        && approvals_required(deps.storage)?
+    {
+        return Err(ContractError::ApprovalRequired {});
+    }
+
+    SCHEDULED_ACTIONS.remove(deps.storage, id);
+    let res = apply_admin_action(deps, env, scheduled.action)?;
+    Ok(res.add_attribute("scheduled_id", id.to_string()))
+}
+
+pub fn cancel_scheduled(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let minter = TOKEN_INFO.load(deps.storage)?.mint.map(|mint| mint.minter);
+    let guardian = TIMELOCK
+        .may_load(deps.storage)?
+        .and_then(|config| config.guardian);
+    if minter.as_ref() != Some(&info.sender) && guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !SCHEDULED_ACTIONS.has(deps.storage, id) {
+        return Err(ContractError::ActionNotFound { id });
+    }
+    SCHEDULED_ACTIONS.remove(deps.storage, id);
+
+    Ok(Response::new().add_attributes(vec![attr("method", "cancel"), attr("id", id.to_string())]))
+}
+
+fn approvals_required(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(APPROVAL_CONFIG
+        .may_load(storage)?
+        .map(|config| !config.approvers.is_empty())
+        .unwrap_or_default())
+}
+
+fn validate_approvers(
+    deps: Deps,
+    approvers: &[String],
+    threshold: u32,
+    voting_period: u64,
+) -> Result<ApprovalConfig, ContractError> {
+    let invalid = |reason: &str| ContractError::InvalidApprovalConfig {
+        reason: reason.to_string(),
+    };
+
+    let approvers = approvers
+        .iter()
+        .map(|approver| normalize(deps, approver))
+        .collect::<Result<Vec<_>, _>>()?;
+    if !approvers.is_empty() {
+        let mut unique = approvers.clone();
+        unique.sort();
+        unique.dedup();
+        if unique.len() != approvers.len() {
+            return Err(invalid("duplicate approver"));
+        }
+        if threshold == 0 || threshold as usize > approvers.len() {
+            return Err(invalid(
+                "threshold must be between 1 and the number of approvers",
+            ));
+        }
+        if voting_period == 0 {
+            return Err(invalid("voting period must be positive"));
+        }
+    }
+
+    Ok(ApprovalConfig {
+        approvers,
+        threshold,
+        voting_period,
+    })
+}
+
+pub fn update_approvers(
+    deps: DepsMut,
+    approvers: Vec<String>,
+    threshold: u32,
+    voting_period: u64,
+) -> Result<Response, ContractError> {
+    let config = validate_approvers(deps.as_ref(), &approvers, threshold, voting_period)?;
+    APPROVAL_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_approvers"),
+        attr("approvers", config.approvers.len().to_string()),
+        attr("threshold", threshold.to_string()),
+    ]))
+}
+
+/// Validates `action` and normalizes its addresses so a proposal records exactly what will run.
+fn normalize_proposal_action(
+    deps: Deps,
+    action: ProposalAction,
+) -> Result<ProposalAction, ContractError> {
+    Ok(match action {
+        ProposalAction::DestroyBlockedFunds { address } => {
+            let address = normalize(deps, &address)?;
+            if !is_blocked(deps, &address)? {
+                return Err(ContractError::NotBlocked {});
+            }
+            ProposalAction::DestroyBlockedFunds {
+                address: address.into_string(),
+            }
+        }
+        ProposalAction::DestroyUnvestedFunds { address } => {
+            let address = normalize(deps, &address)?;
+            if !is_blocked(deps, &address)? {
+                return Err(ContractError::NotBlocked {});
+            }
+            ProposalAction::DestroyUnvestedFunds {
+                address: address.into_string(),
+            }
+        }
+        ProposalAction::UpdateMinter { address } => ProposalAction::UpdateMinter {
+            address: normalize(deps, &address)?.into_string(),
+        },
+        ProposalAction::UpdateApprovers {
+            approvers,
+            threshold,
+            voting_period,
+        } => {
+            let config = validate_approvers(deps, &approvers, threshold, voting_period)?;
+            ProposalAction::UpdateApprovers {
+                approvers: config
+                    .approvers
+                    .into_iter()
+                    .map(Addr::into_string)
+                    .collect(),
+                threshold,
+                voting_period,
+            }
+        }
+        ProposalAction::RecoverAccount {
+            old,
+            new,
+            reference,
+        } => {
+            if reference.trim().is_empty() || reference.len() > 512 {
+                return Err(ContractError::InvalidLegalReference {});
+            }
+            ProposalAction::RecoverAccount {
+                old: normalize(deps, &old)?.into_string(),
+                new: normalize(deps, &new)?.into_string(),
+                reference,
+            }
+        }
+        ProposalAction::SetRole {
+            role,
+            address,
+            granted,
+        } => ProposalAction::SetRole {
+            role,
+            address: normalize(deps, &address)?.into_string(),
+            granted,
+        },
+    })
+}
+
+fn load_approvers(storage: &dyn Storage, sender: &Addr) -> Result<ApprovalConfig, ContractError> {
+    let config = APPROVAL_CONFIG
+        .may_load(storage)?
+        .ok_or(ContractError::Unauthorized {})?;
+    if !config.approvers.contains(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(config)
+}
+
+pub fn propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: ProposalAction,
+) -> Result<Response, ContractError> {
+    let config = load_approvers(deps.storage, &info.sender)?;
+    let action = normalize_proposal_action(deps.as_ref(), action)?;
+
+    let id = NEXT_PROPOSAL_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_PROPOSAL_ID.save(deps.storage, &(id + 1))?;
+    let proposal = Proposal {
+        action,
+        proposer: info.sender.clone(),
+        status: ProposalStatus::Open,
+        expires: env.block.time.plus_seconds(config.voting_period),
+        threshold: config.threshold,
+        approvals: 0,
+        rejections: 0,
+    };
+
+    let res = record_vote(deps, env, info, &config, id, proposal, true)?;
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("method", "propose"),
+            attr("proposal_id", id.to_string()),
+        ])
+        .add_attributes(res.attributes))
+}
+
+pub fn vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    approve: bool,
+) -> Result<Response, ContractError> {
+    let config = load_approvers(deps.storage, &info.sender)?;
+    let proposal = PROPOSALS
+        .may_load(deps.storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound { id: proposal_id })?;
+    if proposal.status != ProposalStatus::Open || env.block.time >= proposal.expires {
+        return Err(ContractError::ProposalClosed { id: proposal_id });
+    }
+    if VOTES.has(deps.storage, (proposal_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    record_vote(deps, env, info, &config, proposal_id, proposal, approve)
+}
+
+/// Counts a vote and runs the proposal once it reaches its threshold. A proposal is rejected
+/// as soon as enough approvers have voted against it that the threshold can't be met.
+fn record_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: &ApprovalConfig,
+    id: u64,
+    mut proposal: Proposal,
+    approve: bool,
+) -> Result<Response, ContractError> {
+    VOTES.save(deps.storage, (id, &info.sender), &approve)?;
+    if approve {
+        proposal.approvals += 1;
+    } else {
+        proposal.rejections += 1;
+    }
+
+    let mut res = Response::new().add_attributes(vec![
+        attr("method", "vote"),
+        attr("proposal_id", id.to_string()),
+        attr("voter", info.sender.as_str()),
+        attr("approve", approve.to_string()),
+    ]);
+    let max_rejections = (config.approvers.len() as u32).saturating_sub(proposal.threshold);
+    if proposal.approvals >= proposal.threshold {
+        proposal.status = ProposalStatus::Executed;
+        save_proposal(deps.storage, id, &proposal)?;
+        let executed = execute_proposal(deps, env, info, proposal.action)?;
+        res = res
+            .add_attribute("status", "executed")
+            .add_attributes(executed.attributes)
+            .add_submessages(executed.messages)
+            .add_events(executed.events);
+    } else {
+        if proposal.rejections > max_rejections {
+            proposal.status = ProposalStatus::Rejected;
+            res = res.add_attribute("status", "rejected");
+        }
+        save_proposal(deps.storage, id, &proposal)?;
+    }
+    Ok(res)
+}
+
+/// Saves `proposal` and keeps it in `OPEN_PROPOSALS` only while it is open.
+fn save_proposal(storage: &mut dyn Storage, id: u64, proposal: &Proposal) -> StdResult<()> {
+    PROPOSALS.save(storage, id, proposal)?;
+    let key = (proposal.expires.seconds(), id);
+    if proposal.status == ProposalStatus::Open {
+        OPEN_PROPOSALS.save(storage, key, &true)
+    } else {
+        OPEN_PROPOSALS.remove(storage, key);
+        Ok(())
+    }
+}
+
+fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: ProposalAction,
+) -> Result<Response, ContractError> {
+    match action {
+        ProposalAction::DestroyBlockedFunds { address } => {
+            destroy_blocked_funds(deps, env, info, address)
+        }
+        ProposalAction::DestroyUnvestedFunds { address } => {
+            destroy_unvested_funds(deps, env, info, address)
+        }
+        ProposalAction::UpdateMinter { address } => {
+            let delay = timelock_delay(deps.storage)?;
+            let action = TimelockAction::UpdateMinter { address };
+            if delay > 0 {
+                enqueue_action(
+                    deps.storage,
+                    action,
+                    env.block.time.plus_seconds(delay),
+                    true,
+                )
+            } else {
+                apply_admin_action(deps, env, action)
+            }
+        }
+        ProposalAction::UpdateApprovers {
+            approvers,
+            threshold,
+            voting_period,
+        } => update_approvers(deps, approvers, threshold, voting_period),
+        ProposalAction::RecoverAccount {
+            old,
+            new,
+            reference,
+        } => recover_account(deps, env, old, new, reference),
+        ProposalAction::SetRole {
+            role,
+            address,
+            granted,
+        } => set_role(deps, role, address, granted),
+    }
+}
+
+pub fn set_role(
+    deps: DepsMut,
+    role: Role,
+    address: String,
+    granted: bool,
+) -> Result<Response, ContractError> {
+    let address = normalize(deps.as_ref(), &address)?;
+
+    if granted {
+        ROLES.save(deps.storage, (role.as_str(), &address), &true)?;
+    } else {
+        ROLES.remove(deps.storage, (role.as_str(), &address));
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "set_role"),
+        attr("role", role.as_str()),
+        attr("address", address),
+        attr("granted", granted.to_string()),
+    ]))
+}
+
+fn has_role(storage: &dyn Storage, role: Role, address: &Addr) -> StdResult<bool> {
+    Ok(ROLES
+        .may_load(storage, (role.as_str(), address))?
+        .unwrap_or_default())
+}
+
+/// Returns the pending review of `address`, if any. Only the latest review can be pending.
+fn pending_review(storage: &dyn Storage, address: &Addr) -> StdResult<Option<(u64, Review)>> {
+    let latest = reviews()
+        .idx
+        .address
+        .prefix(address.clone())
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?;
+    Ok(latest.filter(|(_, review)| review.status == ReviewStatus::Pending))
+}
+
+pub fn request_review(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    evidence_uri: String,
+) -> Result<Response, ContractError> {
+    let address = normalize(deps.as_ref(), info.sender.as_str())?;
+    if !is_blocked(deps.as_ref(), &address)? {
+        return Err(ContractError::NotBlocked {});
+    }
+    if evidence_uri.trim().is_empty() || evidence_uri.len() > 512 {
+        return Err(ContractError::InvalidEvidenceUri {});
+    }
+    if pending_review(deps.storage, &address)?.is_some() {
+        return Err(ContractError::ReviewPending {});
+    }
+
+    let id = NEXT_REVIEW_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_REVIEW_ID.save(deps.storage, &(id + 1))?;
+    let review = Review {
+        address: address.clone(),
+        evidence_uri,
+        status: ReviewStatus::Pending,
+        requested_at: env.block.time,
+        resolved_by: None,
+        resolved_at: None,
+        note: None,
+    };
+    reviews().save(deps.storage, id, &review)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "request_review"),
+        attr("address", address),
+        attr("review_id", id.to_string()),
+    ]))
+}
+
+/// Closes the pending review of `address`. Unblocking is queued behind the timelock when one
+/// is configured, just as a direct `RemoveFromBlockedList` would have to be.
+pub fn resolve_review(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    decision: ReviewDecision,
+    note: String,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, Role::Compliance, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let address = normalize(deps.as_ref(), &address)?;
+    let (id, mut review) =
+        pending_review(deps.storage, &address)?.ok_or(ContractError::NoPendingReview {})?;
+
+    review.status = match decision {
+        ReviewDecision::Unblock => ReviewStatus::Unblocked,
+        ReviewDecision::Uphold => ReviewStatus::Upheld,
+    };
+    review.resolved_by = Some(info.sender);
+    review.resolved_at = Some(env.block.time);
+    review.note = Some(note);
+    reviews().save(deps.storage, id, &review)?;
+
+    let res = Response::new().add_attributes(vec![
+        attr("method", "resolve_review"),
+        attr("address", address.as_str()),
+        attr("review_id", id.to_string()),
+        attr("status", review.status.as_str()),
+    ]);
+    if decision == ReviewDecision::Uphold {
+        return Ok(res);
+    }
+
+    let action = TimelockAction::RemoveFromBlockedList {
+        address: address.into_string(),
+    };
+    let delay = timelock_delay(deps.storage)?;
+    let unblocked = if delay > 0 {
+        enqueue_action(
+            deps.storage,
+            action,
+            env.block.time.plus_seconds(delay),
+            false,
+        )?
+    } else {
+        apply_admin_action(deps, env, action)?
+    };
+    Ok(res
+        .add_attributes(unblocked.attributes)
+        .add_submessages(unblocked.messages))
+}
+
+pub fn update_address_prefix(
+    deps: DepsMut,
+    prefix: Option<String>,
+) -> Result<Response, ContractError> {
+    match &prefix {
+        Some(prefix) => {
+            if prefix.is_empty()
+                || !prefix
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            {
+                return Err(
+                    StdError::generic_err("Address prefix must be lowercase alphanumeric").into(),
+                );
+            }
+            ADDRESS_PREFIX.save(deps.storage, prefix)?
+        }
+        None => ADDRESS_PREFIX.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_address_prefix"),
+        attr("prefix", prefix.unwrap_or_default()),
+    ]))
+}
+
+pub fn update_max_balance(
+    deps: DepsMut,
+    max_balance: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    match max_balance {
+        Some(max_balance) => MAX_BALANCE.save(deps.storage, &max_balance)?,
+        None => MAX_BALANCE.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_max_balance"),
+        attr(
+            "max_balance",
+            max_balance.map_or_else(|| "none".to_string(), |m| m.to_string()),
+        ),
+    ]))
+}
+
+pub fn set_max_balance_exempt(
+    deps: DepsMut,
+    address: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    let address = normalize(deps.as_ref(), &address)?;
+
+    if exempt {
+        MAX_BALANCE_EXEMPT.save(deps.storage, &address, &true)?;
+    } else {
+        MAX_BALANCE_EXEMPT.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "set_max_balance_exempt"),
+        attr("address", address),
+        attr("exempt", exempt.to_string()),
+    ]))
+}
+
+/// Returns the maximum balance `address` may hold, `None` if it is unlimited.
+fn max_balance_for(deps: Deps, address: &Addr) -> StdResult<Option<Uint128>> {
+    if MAX_BALANCE_EXEMPT
+        .may_load(deps.storage, address)?
+        .unwrap_or_default()
+    {
+        return Ok(None);
+    }
+    MAX_BALANCE.may_load(deps.storage)
+}
+
+/// Ensures crediting `amount` to `recipient` keeps it within the maximum balance.
+/// A transfer to oneself does not change the balance and is always allowed.
+fn check_max_balance(
+    deps: Deps,
+    from: Option<&Addr>,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if from == Some(recipient) {
+        return Ok(());
+    }
+
+    if let Some(max_balance) = max_balance_for(deps, recipient)? {
+        let balance = BALANCES
+            .may_load(deps.storage, recipient)?
+            .unwrap_or_default();
+        if balance.checked_add(amount).map_err(StdError::from)? > max_balance {
+            return Err(ContractError::MaxBalanceExceeded {});
+        }
+    }
+    Ok(())
+}
+
+pub fn update_fee_config(
+    deps: DepsMut,
+    collector: String,
+    basis_points: u16,
+    min_fee: Uint128,
+    max_fee: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let collector = normalize(deps.as_ref(), &collector)?;
+    if is_blocked(deps.as_ref(), &collector)? {
+        return Err(ContractError::BlockedFeeCollector {});
+    }
+    if basis_points > 10_000 {
+        return Err(ContractError::InvalidFeeConfig {
+            reason: "basis_points cannot exceed 10000".to_string(),
+        });
+    }
+    if matches!(max_fee, Some(max_fee) if max_fee < min_fee) {
+        return Err(ContractError::InvalidFeeConfig {
+            reason: "max_fee cannot be lower than min_fee".to_string(),
+        });
+    }
+
+    FEE_CONFIG.save(
+        deps.storage,
+        &FeeConfig {
+            collector: collector.clone(),
+            basis_points,
+            min_fee,
+            max_fee,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_fee_config"),
+        attr("collector", collector),
+        attr("basis_points", basis_points.to_string()),
+    ]))
+}
+
+pub fn set_fee_exempt(
+    deps: DepsMut,
+    address: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    let address = normalize(deps.as_ref(), &address)?;
+
+    if exempt {
+        FEE_EXEMPT.save(deps.storage, &address, &true)?;
+    } else {
+        FEE_EXEMPT.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "set_fee_exempt"),
+        attr("address", address),
+        attr("exempt", exempt.to_string()),
+    ]))
+}
+
+pub fn update_send_whitelist(deps: DepsMut, enabled: bool) -> Result<Response, ContractError> {
+    SEND_WHITELIST_ENABLED.save(deps.storage, &enabled)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "update_send_whitelist"),
+        attr("enabled", enabled.to_string()),
+    ]))
+}
+
+pub fn set_send_whitelisted(
+    deps: DepsMut,
+    contract: String,
+    whitelisted: bool,
+) -> Result<Response, ContractError> {
+    let contract = normalize(deps.as_ref(), &contract)?;
+
+    if whitelisted {
+        SEND_WHITELIST.save(deps.storage, &contract, &true)?;
+    } else {
+        SEND_WHITELIST.remove(deps.storage, &contract);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "set_send_whitelisted"),
+        attr("contract", contract),
+        attr("whitelisted", whitelisted.to_string()),
+    ]))
+}
+
+/// Applies the blocklist, vesting, send whitelist, fee and maximum balance policies to moving
+/// `amount` from `from` to `to`, and returns the fee to split off. The maximum balance applies
+/// to the fee collector too. `Send` and `SendFrom` set `to_contract` so the receiving contract
+/// is vetted as well.
+fn check_transfer(
+    deps: Deps,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+    to_contract: bool,
+) -> Result<TransferFee, ContractError> {
+    if is_blocked(deps, from)? {
+        return Err(ContractError::Blocked {});
+    }
+    check_unlocked(deps, env, from, amount)?;
+    if to_contract {
+        check_send_contract(deps, to)?;
+    }
+    let fee = transfer_fee(deps, from, to, amount)?;
+    let net = amount.checked_sub(fee.amount).map_err(StdError::from)?;
+    check_max_balance(deps, Some(from), to, net)?;
+    if let Some(collector) = &fee.collector {
+        check_max_balance(deps, Some(from), collector, fee.amount)?;
+    }
+    Ok(fee)
+}
+
+/// Ensures `from` holds `amount` and, for `TransferFrom` and `SendFrom`, that `spender` may
+/// move it. These are the checks cw20-base makes, in the same order, reported with their
+/// restriction codes instead of an overflow.
+fn check_funds(
+    deps: Deps,
+    env: &Env,
+    from: &Addr,
+    spender: Option<&Addr>,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if let Some(spender) = spender {
+        let allowance = ALLOWANCES
+            .may_load(deps.storage, (from, spender))?
+            .ok_or(ContractError::NoAllowance {})?;
+        if allowance.expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        if allowance.allowance < amount {
+            return Err(ContractError::InsufficientAllowance {});
+        }
+    }
+    let balance = BALANCES.may_load(deps.storage, from)?.unwrap_or_default();
+    if balance < amount {
+        return Err(ContractError::InsufficientBalance {});
+    }
+    Ok(())
+}
+
+/// Ensures the contract receiving a `Send` or `SendFrom` is not blocked and, if the send
+/// whitelist is enabled, has been vetted.
+fn check_send_contract(deps: Deps, contract: &Addr) -> Result<(), ContractError> {
+    if is_blocked(deps, contract)? {
+        return Err(ContractError::Blocked {});
+    }
+
+    let whitelist_enabled = SEND_WHITELIST_ENABLED
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    if whitelist_enabled
+        && !SEND_WHITELIST
+            .may_load(deps.storage, contract)?
+            .unwrap_or_default()
+    {
+        return Err(ContractError::ContractNotWhitelisted {});
+    }
+    Ok(())
+}
+
+/// The fee charged on a single transfer. `collector` is `None` when no fee applies.
+struct TransferFee {
+    collector: Option<Addr>,
+    amount: Uint128,
+}
+
+impl TransferFee {
+    fn none() -> Self {
+        TransferFee {
+            collector: None,
+            amount: Uint128::zero(),
+        }
+    }
+
+    fn attributes(&self) -> Vec<Attribute> {
+        match &self.collector {
+            Some(collector) => vec![attr("fee", self.amount), attr("fee_collector", collector)],
+            None => vec![],
+        }
+    }
+}
+
+/// Computes the fee for moving `amount` from `from` to `to`. No fee is charged if either side
+/// is exempt or is the collector, or if the collector has since been blocked.
+fn transfer_fee(
+    deps: Deps,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> Result<TransferFee, ContractError> {
+    let config = match FEE_CONFIG.may_load(deps.storage)? {
+        Some(config) => config,
+        None => return Ok(TransferFee::none()),
+    };
+
+    for address in [from, to] {
+        if *address == config.collector
+            || FEE_EXEMPT
+                .may_load(deps.storage, address)?
+                .unwrap_or_default()
+        {
+            return Ok(TransferFee::none());
+        }
+    }
+    if is_blocked(deps, &config.collector)? {
+        return Ok(TransferFee::none());
+    }
+
+    let mut fee = amount.multiply_ratio(config.basis_points, 10_000u128);
+    fee = fee.max(config.min_fee);
+    if let Some(max_fee) = config.max_fee {
+        fee = fee.min(max_fee);
+    }
+    fee = fee.min(amount);
+
+    if fee.is_zero() {
+        return Ok(TransferFee::none());
+    }
+    Ok(TransferFee {
+        collector: Some(config.collector),
+        amount: fee,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::IsBlocked { address } => to_binary(&query_blocked(deps, address)?),
+        // inherited from cw20-base
+        QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
+        QueryMsg::Balance { address } => {
+            let address = normalize_query(deps, &address)?;
+            to_binary(&query_balance(deps, address.into_string())?)
+        }
+        QueryMsg::AllAccounts { start_after, limit } => {
+            let start_after = start_after
+                .map(|address| normalize_query(deps, &address).map(Addr::into_string))
+                .transpose()?;
+            to_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
+        QueryMsg::Allowance { owner, spender } => {
+            let owner = normalize_query(deps, &owner)?;
+            let spender = normalize_query(deps, &spender)?;
+            to_binary(&query_allowance(
+                deps,
+                owner.into_string(),
+                spender.into_string(),
+            )?)
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => {
+            let owner = normalize_query(deps, &owner)?;
+            let start_after = start_after
+                .map(|address| normalize_query(deps, &address).map(Addr::into_string))
+                .transpose()?;
+            to_binary(&query_all_allowances(
+                deps,
+                owner.into_string(),
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::Minter {} => to_binary(&query_minter(deps)?),
+        QueryMsg::MaxBalance { address } => to_binary(&query_max_balance(deps, address)?),
+        QueryMsg::FeeConfig {} => to_binary(&FEE_CONFIG.may_load(deps.storage)?),
+        QueryMsg::BalanceAt { address, height } => {
+            to_binary(&query_balance_at(deps, address, height)?)
+        }
+        QueryMsg::TotalSupplyAt { height } => to_binary(&query_total_supply_at(deps, height)?),
+        QueryMsg::SendWhitelist { start_after, limit } => {
+            let start_after = start_after
+                .map(|address| normalize_query(deps, &address).map(Addr::into_string))
+                .transpose()?;
+            to_binary(&query_send_whitelist(deps, start_after, limit)?)
+        }
+        QueryMsg::Timelock {} => to_binary(&query_timelock(deps)?),
+        QueryMsg::PendingActions { start_after, limit } => {
+            to_binary(&query_pending_actions(deps, start_after, limit)?)
+        }
+        QueryMsg::Approvers {} => to_binary(&query_approvers(deps)?),
+        QueryMsg::Proposal { proposal_id } => to_binary(&query_proposal(deps, env, proposal_id)?),
+        QueryMsg::OpenProposals { start_after, limit } => {
+            to_binary(&query_open_proposals(deps, env, start_after, limit)?)
+        }
+        QueryMsg::ProposalVotes {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_binary(&query_proposal_votes(
+            deps,
+            proposal_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::CanTransfer {
+            from,
+            to,
+            amount,
+            spender,
+        } => to_binary(&query_can_transfer(deps, env, from, to, amount, spender)?),
+        QueryMsg::RestrictionMessage { code } => to_binary(&query_restriction_message(code)?),
+        QueryMsg::AccountStatus { address } => {
+            to_binary(&query_account_status(deps, env, address)?)
+        }
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::IbcChannels {} => to_binary(&query_ibc_channels(deps)?),
+        QueryMsg::Reserves {} => to_binary(&query_reserves(deps, env)?),
+        QueryMsg::VestingSchedule { address } => {
+            to_binary(&query_vesting_schedule(deps, env, address)?)
+        }
+        QueryMsg::Nonce { address } => to_binary(&query_nonce(deps, address)?),
+        QueryMsg::Hold { id } => to_binary(&query_hold(deps, id)?),
+        QueryMsg::HoldsBySender {
+            sender,
+            start_after,
+            limit,
+        } => to_binary(&query_holds_by_sender(deps, sender, start_after, limit)?),
+        QueryMsg::HoldsByRecipient {
+            recipient,
+            start_after,
+            limit,
+        } => to_binary(&query_holds_by_recipient(
+            deps,
+            recipient,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Request { id } => to_binary(&query_request(deps, id)?),
+        QueryMsg::RequestsByStatus {
+            status,
+            start_after,
+            limit,
+        } => to_binary(&query_requests_by_status(deps, status, start_after, limit)?),
+        QueryMsg::RequestsByRequester {
+            requester,
+            start_after,
+            limit,
+        } => to_binary(&query_requests_by_requester(
+            deps,
+            requester,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::LatestAttestation {} => to_binary(&query_latest_attestation(deps)?),
+        QueryMsg::Attestations { start_after, limit } => {
+            to_binary(&query_attestations(deps, start_after, limit)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => to_binary(&query_role_members(deps, role, start_after, limit)?),
+        QueryMsg::Reviews {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_reviews(deps, address, start_after, limit)?),
+        QueryMsg::ReviewsByStatus {
+            status,
+            start_after,
+            limit,
+        } => to_binary(&query_reviews_by_status(deps, status, start_after, limit)?),
+    }
+}
+
+/// Returns whether the normalized `address` is on the blocklist. Storage errors are propagated
+/// rather than read as "not blocked", so the checks built on this fail closed.
+fn is_blocked(deps: Deps, address: &Addr) -> StdResult<bool> {
+    Ok(BLOCKED.may_load(deps.storage, address)?.unwrap_or_default())
+}
+
+fn query_blocked(deps: Deps, address: String) -> StdResult<BlockedResponse> {
+    let address = normalize_query(deps, &address)?;
+    let record = BLOCK_RECORDS.may_load(deps.storage, &address)?;
+    Ok(BlockedResponse {
+        blocked: is_blocked(deps, &address)?,
+        recovered_to: record.and_then(|record| record.recovered_to),
+    })
+}
+
+fn query_max_balance(deps: Deps, address: String) -> StdResult<MaxBalanceResponse> {
+    let address = normalize_query(deps, &address)?;
+    Ok(MaxBalanceResponse {
+        max_balance: max_balance_for(deps, &address)?,
+        exempt: MAX_BALANCE_EXEMPT
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default(),
+    })
+}
+
+fn query_balance_at(deps: Deps, address: String, height: u64) -> StdResult<BalanceResponse> {
+    let address = normalize_query(deps, &address)?;
+    let balance = BALANCE_SNAPSHOTS
+        .may_load_at_height(deps.storage, &address, height)?
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+fn query_total_supply_at(deps: Deps, height: u64) -> StdResult<TotalSupplyResponse> {
+    let total_supply = TOTAL_SUPPLY_SNAPSHOTS
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default();
+    Ok(TotalSupplyResponse { total_supply })
+}
+
+fn query_timelock(deps: Deps) -> StdResult<TimelockResponse> {
+    let config = TIMELOCK.may_load(deps.storage)?;
+    Ok(TimelockResponse {
+        delay: config
+            .as_ref()
+            .map(|config| config.delay)
+            .unwrap_or_default(),
+        guardian: config
+            .and_then(|config| config.guardian)
+            .map(Addr::into_string),
+    })
+}
+
+fn query_pending_actions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingActionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let actions = SCHEDULED_ACTIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(id, scheduled)| PendingAction {
+                id,
+                action: scheduled.action,
+                eta: scheduled.eta,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(PendingActionsResponse { actions })
+}
+
+fn query_approvers(deps: Deps) -> StdResult<ApproversResponse> {
+    let config = APPROVAL_CONFIG.may_load(deps.storage)?;
+    Ok(match config {
+        Some(config) => ApproversResponse {
+            approvers: config
+                .approvers
+                .into_iter()
+                .map(Addr::into_string)
+                .collect(),
+            threshold: config.threshold,
+            voting_period: config.voting_period,
+        },
+        None => ApproversResponse {
+            approvers: vec![],
+            threshold: 0,
+            voting_period: 0,
+        },
+    })
+}
+
+fn proposal_response(env: &Env, id: u64, proposal: Proposal) -> ProposalResponse {
+    let status = match proposal.status {
+        ProposalStatus::Open if env.block.time >= proposal.expires => ProposalStatus::Expired,
+        status => status,
+    };
+    ProposalResponse {
+        id,
+        action: proposal.action,
+        proposer: proposal.proposer.into_string(),
+        status,
+        expires: proposal.expires,
+        threshold: proposal.threshold,
+        approvals: proposal.approvals,
+        rejections: proposal.rejections,
+    }
+}
+
+fn query_proposal(deps: Deps, env: Env, proposal_id: u64) -> StdResult<ProposalResponse> {
+    let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    Ok(proposal_response(&env, proposal_id, proposal))
+}
+
+fn query_open_proposals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // Proposals expiring at or before the current time can no longer be voted on.
+    let mut start = (env.block.time.seconds(), u64::MAX);
+    if let Some(id) = start_after {
+        let proposal = PROPOSALS.load(deps.storage, id)?;
+        start = start.max((proposal.expires.seconds(), id));
+    }
+
+    let proposals = OPEN_PROPOSALS
+        .keys(
+            deps.storage,
+            Some(Bound::exclusive(start)),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|key| {
+            let (_, id) = key?;
+            let proposal = PROPOSALS.load(deps.storage, id)?;
+            Ok(proposal_response(&env, id, proposal))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ProposalsResponse { proposals })
+}
+
+fn query_proposal_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ProposalVotesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| normalize_query(deps, &address))
+        .transpose()?;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_string().into()));
+
+    let votes = VOTES
+        .prefix(proposal_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(voter, approve)| VoteInfo {
+                voter: voter.into_string(),
+                approve,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(ProposalVotesResponse { votes })
+}
+
+/// Mirrors the `Transfer` and `TransferFrom` handlers: the contract's own policy checks in
+/// the same order, then the balance and allowance checks cw20-base makes.
+fn query_can_transfer(
+    deps: Deps,
+    env: Env,
+    from: String,
+    to: String,
+    amount: Uint128,
+    spender: Option<String>,
+) -> StdResult<CanTransferResponse> {
+    let from = normalize_query(deps, &from)?;
+    let to = normalize_query(deps, &to)?;
+    let spender = spender
+        .map(|spender| normalize_query(deps, &spender))
+        .transpose()?;
+    let refused = |reason: Restriction| CanTransferResponse {
+        allowed: false,
+        code: reason.code(),
+        reason: Some(reason),
+    };
+
+    let refused_by = |err: ContractError| match err.restriction() {
+        Some(reason) => Ok(refused(reason)),
+        None => Err(StdError::generic_err(err.to_string())),
+    };
+
+    if let Err(err) = check_transfer(deps, &env, &from, &to, amount, false) {
+        return refused_by(err);
+    }
+    // cw20-base rejects empty transfers, but lets an empty `TransferFrom` through.
+    if amount.is_zero() && spender.is_none() {
+        return Ok(refused(Restriction::ZeroAmount));
+    }
+    if let Err(err) = check_funds(deps, &env, &from, spender.as_ref(), amount) {
+        return refused_by(err);
+    }
+
+    Ok(CanTransferResponse {
+        allowed: true,
+        code: RESTRICTION_SUCCESS,
+        reason: None,
+    })
+}
+
+fn query_restriction_message(code: u8) -> StdResult<RestrictionMessageResponse> {
+    if code == RESTRICTION_SUCCESS {
+        return Ok(RestrictionMessageResponse {
+            code,
+            restriction: None,
+            message: "No restriction".to_string(),
+        });
+    }
+    let restriction = Restriction::from_code(code)
+        .ok_or_else(|| StdError::generic_err(format!("Unknown restriction code {}", code)))?;
+    Ok(RestrictionMessageResponse {
+        code,
+        restriction: Some(restriction),
+        message: restriction.message().to_string(),
+    })
+}
+
+fn query_account_status(deps: Deps, env: Env, address: String) -> StdResult<AccountStatusResponse> {
+    let address = normalize_query(deps, &address)?;
+    let balance = BALANCES
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    let blocked = is_blocked(deps, &address)?;
+
+    let allowances_granted = ALLOWANCES
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(
+            |item| !matches!(item, Ok((_, allowance)) if allowance.expires.is_expired(&env.block)),
+        )
+        .take(MAX_LIMIT as usize)
+        .map(|item| {
+            item.map(|(spender, allowance)| AllowanceInfo {
+                spender: spender.into_string(),
+                allowance: allowance.allowance,
+                expires: allowance.expires,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    let mut allowances_received = vec![];
+    for owner in
+        ALLOWANCE_SPENDERS
+            .prefix(&address)
+            .keys(deps.storage, None, None, Order::Ascending)
+    {
+        let owner = owner?;
+        let allowance = match ALLOWANCES.may_load(deps.storage, (&owner, &address))? {
+            Some(allowance) => allowance,
+            None => continue,
+        };
+        if allowance.allowance.is_zero() || allowance.expires.is_expired(&env.block) {
+            continue;
+        }
+        allowances_received.push(ReceivedAllowance {
+            owner: owner.into_string(),
+            allowance: allowance.allowance,
+            expires: allowance.expires,
+        });
+        if allowances_received.len() == MAX_LIMIT as usize {
+            break;
+        }
+    }
+
+    let frozen = if blocked {
+        balance
+    } else {
+        match VESTING.may_load(deps.storage, &address)? {
+            Some(vesting) => vesting_amounts(&vesting, env.block.time).1.min(balance),
+            None => Uint128::zero(),
+        }
+    };
+
+    Ok(AccountStatusResponse {
+        balance,
+        blocked,
+        block_record: BLOCK_RECORDS.may_load(deps.storage, &address)?,
+        frozen,
+        allowances_granted,
+        allowances_received,
+    })
+}
+
+fn query_reserves(deps: Deps, env: Env) -> StdResult<ReservesResponse> {
+    let denom = WRAP_DENOM.may_load(deps.storage)?;
+    let balance = match &denom {
+        Some(denom) => {
+            deps.querier
+                .query_balance(env.contract.address, denom)?
+                .amount
+        }
+        None => Uint128::zero(),
+    };
+    Ok(ReservesResponse {
+        denom,
+        wrapped: WRAPPED.may_load(deps.storage)?.unwrap_or_default(),
+        balance,
+    })
+}
+
+fn query_vesting_schedule(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<VestingScheduleResponse> {
+    let address = normalize_query(deps, &address)?;
+    let vesting = match VESTING.may_load(deps.storage, &address)? {
+        Some(vesting) => vesting,
+        None => {
+            return Ok(VestingScheduleResponse {
+                schedule: None,
+                amount: Uint128::zero(),
+                vested: Uint128::zero(),
+                locked: Uint128::zero(),
+                paused: false,
+                paused_for: 0,
+            })
+        }
+    };
+    let (vested, locked) = vesting_amounts(&vesting, env.block.time);
+    let paused_for = match vesting.paused_at {
+        Some(paused_at) => env.block.time.seconds().saturating_sub(paused_at.seconds()),
+        None => 0,
+    };
+    Ok(VestingScheduleResponse {
+        schedule: Some(vesting.schedule),
+        amount: vesting.amount,
+        vested,
+        locked,
+        paused: vesting.paused_at.is_some(),
+        paused_for: vesting.paused_for + paused_for,
+    })
+}
+
+fn query_nonce(deps: Deps, address: String) -> StdResult<NonceResponse> {
+    let address = normalize_query(deps, &address)?;
+    Ok(NonceResponse {
+        nonce: NONCES.may_load(deps.storage, &address)?.unwrap_or_default(),
+    })
+}
+
+fn hold_info(id: u64, hold: Hold) -> HoldInfo {
+    HoldInfo {
+        id,
+        sender: hold.sender.into_string(),
+        recipient: hold.recipient.into_string(),
+        amount: hold.amount,
+        expires: hold.expires,
+        status: hold.status,
+        created_at: hold.created_at,
+        resolved_by: hold.resolved_by.map(Addr::into_string),
+        resolved_at: hold.resolved_at,
+    }
+}
+
+fn query_hold(deps: Deps, id: u64) -> StdResult<HoldInfo> {
+    let hold = holds().load(deps.storage, id)?;
+    Ok(hold_info(id, hold))
+}
+
+fn query_holds_by_sender(
+    deps: Deps,
+    sender: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<HoldsResponse> {
+    let sender = normalize_query(deps, &sender)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let holds = holds()
+        .idx
+        .sender
+        .prefix(sender)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, hold)| hold_info(id, hold)))
+        .collect::<StdResult<_>>()?;
+    Ok(HoldsResponse { holds })
+}
+
+fn query_holds_by_recipient(
+    deps: Deps,
+    recipient: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<HoldsResponse> {
+    let recipient = normalize_query(deps, &recipient)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let holds = holds()
+        .idx
+        .recipient
+        .prefix(recipient)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, hold)| hold_info(id, hold)))
+        .collect::<StdResult<_>>()?;
+    Ok(HoldsResponse { holds })
+}
+
+fn request_info(id: u64, request: IssuanceRequest) -> RequestInfo {
+    RequestInfo {
+        id,
+        kind: request.kind,
+        requester: request.requester.into_string(),
+        amount: request.amount,
+        reference: request.reference,
+        status: request.status,
+        created_at: request.created_at,
+        resolved_by: request.resolved_by.map(Addr::into_string),
+        resolved_at: request.resolved_at,
+        reason: request.reason,
+    }
+}
+
+fn query_request(deps: Deps, id: u64) -> StdResult<RequestInfo> {
+    let request = issuance_requests().load(deps.storage, id)?;
+    Ok(request_info(id, request))
+}
+
+fn query_requests_by_status(
+    deps: Deps,
+    status: RequestStatus,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RequestsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let requests = issuance_requests()
+        .idx
+        .status
+        .prefix(status.as_str().to_string())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, request)| request_info(id, request)))
+        .collect::<StdResult<_>>()?;
+    Ok(RequestsResponse { requests })
+}
+
+fn query_requests_by_requester(
+    deps: Deps,
+    requester: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RequestsResponse> {
+    let requester = normalize_query(deps, &requester)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let requests = issuance_requests()
+        .idx
+        .requester
+        .prefix(requester)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, request)| request_info(id, request)))
+        .collect::<StdResult<_>>()?;
+    Ok(RequestsResponse { requests })
+}
+
+fn attestation_info(id: u64, attestation: Attestation) -> AttestationInfo {
+    AttestationInfo {
+        id,
+        reserves: attestation.reserves,
+        as_of: attestation.as_of,
+        report_uri: attestation.report_uri,
+        hash: attestation.hash,
+        submitted_by: attestation.submitted_by.into_string(),
+        submitted_at: attestation.submitted_at,
+    }
+}
+
+fn query_latest_attestation(deps: Deps) -> StdResult<LatestAttestationResponse> {
+    Ok(LatestAttestationResponse {
+        attestation: latest_attestation(deps.storage)?
+            .map(|(id, attestation)| attestation_info(id, attestation)),
+        enforced: ENFORCE_RESERVES.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+fn query_attestations(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AttestationsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let attestations = ATTESTATIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, attestation)| attestation_info(id, attestation)))
+        .collect::<StdResult<_>>()?;
+    Ok(AttestationsResponse { attestations })
+}
+
+fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    Ok(StatsResponse {
+        total_supply: TOKEN_INFO.load(deps.storage)?.total_supply,
+        holders: stats.holders,
+        blocked_addresses: stats.blocked_addresses,
+        blocked_balance: stats.blocked_balance,
+        destroyed: stats.destroyed,
+        minted: stats.minted,
+        redeemed: stats.redeemed,
+    })
+}
+
+fn query_role_members(
+    deps: Deps,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RoleMembersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| normalize_query(deps, &address))
+        .transpose()?;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_string().into()));
+
+    let members = ROLES
+        .prefix(role.as_str())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(Addr::into_string))
+        .collect::<StdResult<_>>()?;
+    Ok(RoleMembersResponse { members })
+}
+
+fn review_info(id: u64, review: Review) -> ReviewInfo {
+    ReviewInfo {
+        id,
+        address: review.address.into_string(),
+        evidence_uri: review.evidence_uri,
+        status: review.status,
+        requested_at: review.requested_at,
+        resolved_by: review.resolved_by.map(Addr::into_string),
+        resolved_at: review.resolved_at,
+        note: review.note,
+    }
+}
+
+fn query_reviews(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ReviewsResponse> {
+    let address = normalize_query(deps, &address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let reviews = reviews()
+        .idx
+        .address
+        .prefix(address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, review)| review_info(id, review)))
+        .collect::<StdResult<_>>()?;
+    Ok(ReviewsResponse { reviews })
+}
+
+fn query_reviews_by_status(
+    deps: Deps,
+    status: ReviewStatus,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ReviewsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let reviews = reviews()
+        .idx
+        .status
+        .prefix(status.as_str().to_string())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, review)| review_info(id, review)))
+        .collect::<StdResult<_>>()?;
+    Ok(ReviewsResponse { reviews })
+}
+
+fn query_send_whitelist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SendWhitelistResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let contracts = SEND_WHITELIST
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(Into::into))
+        .collect::<StdResult<_>>()?;
+
+    Ok(SendWhitelistResponse {
+        enabled: SEND_WHITELIST_ENABLED
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+        contracts,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // Contracts instantiated before balance snapshots and running totals existed start from
+    // a full count, so later changes can be applied as deltas.
+    if STATS.may_load(deps.storage)?.is_none() {
+        let accounts = BALANCES
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        sync_balances(deps.storage, env.block.height, &accounts)?;
+        rebuild_stats(deps.storage)?;
+    }
+    // Index allowances granted before the spender index existed.
+    let pairs = ALLOWANCES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (owner, spender) in pairs {
+        ALLOWANCE_SPENDERS.save(deps.storage, (&spender, &owner), &true)?;
+    }
+    // Index proposals opened before open proposals had their own index.
+    let proposals = PROPOSALS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, proposal) in proposals {
+        save_proposal(deps.storage, id, &proposal)?;
+    }
+    Ok(Response::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cw20::TokenInfoResponse;
+
+    use super::*;
+    fn get_balance<T: Into<String>>(deps: Deps, address: T) -> Uint128 {
+        query_balance(deps, address.into()).unwrap().balance
+    }
+
+    // this will set up the instantiation for other tests
+    fn do_instantiate(mut deps: DepsMut) -> TokenInfoResponse {
+        let instantiate_msg = InstantiateMsg {
+            name: "Auto Gen".to_string(),
+            symbol: "AUTO".to_string(),
+            decimals: 6,
+        };
+        let info = mock_info("creator", &[]);
+        let env = mock_env();
+        let res = instantiate(deps.branch(), env, info, instantiate_msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let meta = query_token_info(deps.as_ref()).unwrap();
+        assert_eq!(
+            meta,
+            TokenInfoResponse {
+                name: "Auto Gen".to_string(),
                 symbol: "AUTO".to_string(),
                 decimals: 6,
                 total_supply: Uint128::zero(),
             }
-        );
-        meta
+        );
+        meta
+    }
+
+    /// A secp256k1 key and the `cosmos` address it controls, for signed messages.
+    struct TestKey(k256::ecdsa::SigningKey);
+
+    impl TestKey {
+        fn new(seed: u8) -> Self {
+            TestKey(k256::ecdsa::SigningKey::from_bytes(&[seed; 32]).unwrap())
+        }
+
+        fn pubkey(&self) -> Binary {
+            Binary::from(&self.0.verifying_key().to_bytes()[..])
+        }
+
+        fn address(&self) -> String {
+            use bech32::ToBase32;
+            use ripemd160::Ripemd160;
+            use sha2::{Digest, Sha256};
+
+            let hash = Ripemd160::digest(&Sha256::digest(self.pubkey().as_slice()));
+            bech32::encode("cosmos", hash.to_base32(), bech32::Variant::Bech32).unwrap()
+        }
+
+        fn sign<T: serde::Serialize>(&self, payload: &T) -> Binary {
+            use k256::ecdsa::signature::DigestSigner;
+            use sha2::{Digest, Sha256};
+
+            let digest = Sha256::new().chain(to_vec(payload).unwrap());
+            let signature: k256::ecdsa::Signature = self.0.sign_digest(digest);
+            Binary::from(signature.as_ref())
+        }
+    }
+
+    mod instantiate {
+        use super::*;
+
+        #[test]
+        fn basic() {
+            let mut deps = mock_dependencies();
+            let amount = Uint128::new(11223344);
+            do_instantiate(deps.as_mut());
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: amount,
+            };
+
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            assert_eq!(
+                query_token_info(deps.as_ref()).unwrap(),
+                TokenInfoResponse {
+                    name: "Auto Gen".to_string(),
+                    symbol: "AUTO".to_string(),
+                    decimals: 6,
+                    total_supply: amount,
+                }
+            );
+            assert_eq!(
+                get_balance(deps.as_ref(), "addr0000"),
+                Uint128::new(11223344)
+            );
+        }
+
+        #[test]
+        fn blocked() {
+            let mut deps = mock_dependencies();
+            let amount = Uint128::from(11223344u128);
+            do_instantiate(deps.as_mut());
+
+            // Mint to addr0000 from creator.
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: amount,
+            };
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            // Block addr0000 from creator.
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            // Attempt a transfer from addr0000 to addr0001.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::from(1000000u128),
+            };
+            let info = mock_info("addr0000", &[]);
+            let env = mock_env();
+
+            // Ensure transfer was blocked.
+            let err = execute(deps.as_mut(), env, info, msg.clone()).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+
+            // Unblock addr0000.
+            let unblock_msg = ExecuteMsg::RemoveFromBlockedList {
+                address: "addr0000".into(),
+            };
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, unblock_msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            // Attempt a transfer from addr0000 to addr0001.
+            let info = mock_info("addr0000", &[]);
+            let env = mock_env();
+
+            // Ensure transfer was successful.
+            let res = execute(deps.as_mut(), env, info, msg.clone()).unwrap();
+            assert_eq!(0, res.messages.len());
+            assert_eq!(
+                get_balance(deps.as_ref(), "addr0001"),
+                Uint128::new(1000000)
+            );
+        }
+
+        #[test]
+        fn ensure_uppercase_address_can_not_evade() {
+            let mut deps = mock_dependencies();
+            let amount = Uint128::new(11223344);
+            do_instantiate(deps.as_mut());
+
+            // Mint to uppercased address ADDR0000 from creator.
+            let msg = ExecuteMsg::Mint {
+                recipient: "ADDR0000".into(),
+                amount: amount,
+            };
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            // Block lowercase addr0000 from creator.
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            // Attempt a transfer from uppercased address ADDR0000 to addr0001.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::from(1000000u128),
+            };
+            let info = mock_info("ADDR0000", &[]);
+            let env = mock_env();
+
+            // Ensure transfer was blocked.
+            let err = execute(deps.as_mut(), env, info, msg.clone()).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+        }
+
+        #[test]
+        fn destroy_blocked_funds() {
+            let mut deps = mock_dependencies();
+            let amount = Uint128::from(11223344u128);
+            do_instantiate(deps.as_mut());
+
+            // Mint to addr0000 from creator.
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: amount,
+            };
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            // Attempt destroy
+            let msg = ExecuteMsg::DestroyBlockedFunds {
+                address: "addr0000".into(),
+            };
+
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            // ensure you can't burn destroy that aren't blocked.
+            let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(err, ContractError::NotBlocked {});
+
+            // Block addr0000 from creator.
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            // Destroy blocked funds.
+            let msg = ExecuteMsg::DestroyBlockedFunds {
+                address: "addr0000".into(),
+            };
+
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::zero());
+        }
+
+        #[test]
+        fn blocked_minter_cannot_redeem() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "creator".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "creator".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Redeem {
+                amount: Uint128::new(1000),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+            assert_eq!(get_balance(deps.as_ref(), "creator"), Uint128::new(1000));
+        }
+
+        #[test]
+        fn invalid_addresses_fail_closed() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+
+            // Too short to be a valid address in the mock api.
+            let err = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::IsBlocked {
+                    address: "xy".into(),
+                },
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                StdError::generic_err(
+                    ContractError::InvalidAddress {
+                        address: "xy".into()
+                    }
+                    .to_string()
+                )
+            );
+
+            let msg = ExecuteMsg::DestroyBlockedFunds {
+                address: "xy".into(),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidAddress {
+                    address: "xy".into()
+                }
+            );
+
+            let msg = ExecuteMsg::TransferFrom {
+                owner: "xy".into(),
+                recipient: "addr0001".into(),
+                amount: Uint128::new(1),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("spender", &[]), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidAddress {
+                    address: "xy".into()
+                }
+            );
+        }
+
+        #[test]
+        fn queries_work() {
+            let mut deps = mock_dependencies();
+            let env = mock_env();
+            do_instantiate(deps.as_mut());
+            let data = query(
+                deps.as_ref(),
+                env,
+                QueryMsg::IsBlocked {
+                    address: String::from("addr0000"),
+                },
+            )
+            .unwrap();
+            let loaded: BlockedResponse = from_binary(&data).unwrap();
+            assert_eq!(loaded.blocked, false);
+
+            // Block addr0000 from creator.
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            let info = mock_info("creator", &[]);
+            let env = mock_env();
+
+            let res = execute(deps.as_mut(), env, info, msg).unwrap();
+            assert_eq!(0, res.messages.len());
+
+            let env = mock_env();
+            let data = query(
+                deps.as_ref(),
+                env,
+                QueryMsg::IsBlocked {
+                    address: String::from("addr0000"),
+                },
+            )
+            .unwrap();
+            let loaded: BlockedResponse = from_binary(&data).unwrap();
+            assert_eq!(loaded.blocked, true);
+        }
+    }
+    mod max_balance {
+        use super::*;
+
+        fn set_max_balance(deps: DepsMut, max_balance: Option<Uint128>) {
+            let msg = ExecuteMsg::UpdateMaxBalance { max_balance };
+            let info = mock_info("creator", &[]);
+            execute(deps, mock_env(), info, msg).unwrap();
+        }
+
+        #[test]
+        fn mint_and_transfer_respect_limit() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            set_max_balance(deps.as_mut(), Some(Uint128::new(1000)));
+
+            // Minting over the limit fails.
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1001),
+            };
+            let info = mock_info("creator", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(err, ContractError::MaxBalanceExceeded {});
+
+            // Minting up to the limit works.
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(500),
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            // addr0000 is full, so it can't receive from addr0001.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1),
+            };
+            let info = mock_info("addr0001", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(err, ContractError::MaxBalanceExceeded {});
+
+            // The same applies to spending an allowance.
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".into(),
+                amount: Uint128::new(500),
+                expires: None,
+            };
+            let info = mock_info("addr0001", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let msg = ExecuteMsg::TransferFrom {
+                owner: "addr0001".into(),
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1),
+            };
+            let info = mock_info("spender", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(err, ContractError::MaxBalanceExceeded {});
+
+            // Transfers to oneself don't change the balance.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            let info = mock_info("addr0000", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            // Removing the limit lets the transfer through.
+            set_max_balance(deps.as_mut(), None);
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(500),
+            };
+            let info = mock_info("addr0001", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(1500));
+        }
+
+        #[test]
+        fn exempt_addresses() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            set_max_balance(deps.as_mut(), Some(Uint128::new(1000)));
+
+            let msg = ExecuteMsg::SetMaxBalanceExempt {
+                address: "treasury".into(),
+                exempt: true,
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "treasury".into(),
+                amount: Uint128::new(5000),
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let data = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::MaxBalance {
+                    address: "treasury".into(),
+                },
+            )
+            .unwrap();
+            let loaded: MaxBalanceResponse = from_binary(&data).unwrap();
+            assert_eq!(
+                loaded,
+                MaxBalanceResponse {
+                    max_balance: None,
+                    exempt: true,
+                }
+            );
+
+            let data = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::MaxBalance {
+                    address: "addr0000".into(),
+                },
+            )
+            .unwrap();
+            let loaded: MaxBalanceResponse = from_binary(&data).unwrap();
+            assert_eq!(
+                loaded,
+                MaxBalanceResponse {
+                    max_balance: Some(Uint128::new(1000)),
+                    exempt: false,
+                }
+            );
+
+            // Only the minter can manage the limit.
+            let msg = ExecuteMsg::SetMaxBalanceExempt {
+                address: "addr0000".into(),
+                exempt: true,
+            };
+            let info = mock_info("addr0000", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+        }
+    }
+    mod fees {
+        use super::*;
+
+        fn setup_fees(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(100_000),
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.branch(), mock_env(), info, msg).unwrap();
+
+            // 1% fee, at least 10 and at most 500.
+            let msg = ExecuteMsg::UpdateFeeConfig {
+                collector: "collector".into(),
+                basis_points: 100,
+                min_fee: Uint128::new(10),
+                max_fee: Some(Uint128::new(500)),
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps, mock_env(), info, msg).unwrap();
+        }
+
+        #[test]
+        fn transfer_pays_collector() {
+            let mut deps = mock_dependencies();
+            setup_fees(deps.as_mut());
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(10_000),
+            };
+            let info = mock_info("addr0000", &[]);
+            let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            assert!(res.attributes.contains(&attr("fee", "100")));
+            assert!(res.attributes.contains(&attr("fee_collector", "collector")));
+
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(90_000));
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(9_900));
+            assert_eq!(get_balance(deps.as_ref(), "collector"), Uint128::new(100));
+
+            // Small transfers pay the minimum, large ones the maximum.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(100),
+            };
+            let info = mock_info("addr0000", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(9_990));
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(80_000),
+            };
+            let info = mock_info("addr0000", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(89_490));
+            assert_eq!(get_balance(deps.as_ref(), "collector"), Uint128::new(610));
+
+            let data = query(deps.as_ref(), mock_env(), QueryMsg::FeeConfig {}).unwrap();
+            let loaded: Option<FeeConfig> = from_binary(&data).unwrap();
+            assert_eq!(
+                loaded,
+                Some(FeeConfig {
+                    collector: Addr::unchecked("collector"),
+                    basis_points: 100,
+                    min_fee: Uint128::new(10),
+                    max_fee: Some(Uint128::new(500)),
+                })
+            );
+        }
+
+        #[test]
+        fn transfer_from_pays_collector_from_allowance() {
+            let mut deps = mock_dependencies();
+            setup_fees(deps.as_mut());
+
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".into(),
+                amount: Uint128::new(10_000),
+                expires: None,
+            };
+            let info = mock_info("addr0000", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let msg = ExecuteMsg::TransferFrom {
+                owner: "addr0000".into(),
+                recipient: "addr0001".into(),
+                amount: Uint128::new(10_000),
+            };
+            let info = mock_info("spender", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(9_900));
+            assert_eq!(get_balance(deps.as_ref(), "collector"), Uint128::new(100));
+            assert_eq!(
+                query_allowance(deps.as_ref(), "addr0000".into(), "spender".into())
+                    .unwrap()
+                    .allowance,
+                Uint128::zero()
+            );
+        }
+
+        #[test]
+        fn exempt_addresses_pay_nothing() {
+            let mut deps = mock_dependencies();
+            setup_fees(deps.as_mut());
+
+            let msg = ExecuteMsg::SetFeeExempt {
+                address: "exchange".into(),
+                exempt: true,
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "exchange".into(),
+                amount: Uint128::new(10_000),
+            };
+            let info = mock_info("addr0000", &[]);
+            let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            assert!(!res.attributes.iter().any(|a| a.key == "fee"));
+
+            assert_eq!(get_balance(deps.as_ref(), "exchange"), Uint128::new(10_000));
+            assert_eq!(get_balance(deps.as_ref(), "collector"), Uint128::zero());
+        }
+
+        #[test]
+        fn blocked_collector_rejected() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "collector".into(),
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            let msg = ExecuteMsg::UpdateFeeConfig {
+                collector: "collector".into(),
+                basis_points: 100,
+                min_fee: Uint128::zero(),
+                max_fee: None,
+            };
+            let info = mock_info("creator", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(err, ContractError::BlockedFeeCollector {});
+
+            let msg = ExecuteMsg::UpdateFeeConfig {
+                collector: "treasury".into(),
+                basis_points: 10_001,
+                min_fee: Uint128::zero(),
+                max_fee: None,
+            };
+            let info = mock_info("creator", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidFeeConfig { .. }));
+        }
+
+        #[test]
+        fn collector_respects_max_balance() {
+            let mut deps = mock_dependencies();
+            setup_fees(deps.as_mut());
+
+            let msg = ExecuteMsg::UpdateMaxBalance {
+                max_balance: Some(Uint128::new(150)),
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            let msg = ExecuteMsg::SetMaxBalanceExempt {
+                address: "addr0002".into(),
+                exempt: true,
+            };
+            let info = mock_info("creator", &[]);
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+            // The first fee fits, the second would take the collector over the limit.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(100),
+            };
+            let info = mock_info("addr0000", &[]);
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "collector"), Uint128::new(10));
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0002".into(),
+                amount: Uint128::new(14_100),
+            };
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+            assert_eq!(err, ContractError::MaxBalanceExceeded {});
+
+            // Exempting the collector lets fees accrue again.
+            let exempt = ExecuteMsg::SetMaxBalanceExempt {
+                address: "collector".into(),
+                exempt: true,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), exempt).unwrap();
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "collector"), Uint128::new(151));
+        }
+    }
+    mod snapshots {
+        use super::*;
+        use crate::msg::StatsResponse;
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+
+        fn env_at(height: u64) -> Env {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        }
+
+        fn balance_at(deps: Deps, address: &str, height: u64) -> Uint128 {
+            let msg = QueryMsg::BalanceAt {
+                address: address.into(),
+                height,
+            };
+            let data = query(deps, mock_env(), msg).unwrap();
+            from_binary::<BalanceResponse>(&data).unwrap().balance
+        }
+
+        fn total_supply_at(deps: Deps, height: u64) -> Uint128 {
+            let msg = QueryMsg::TotalSupplyAt { height };
+            let data = query(deps, mock_env(), msg).unwrap();
+            from_binary::<TotalSupplyResponse>(&data)
+                .unwrap()
+                .total_supply
+        }
+
+        #[test]
+        fn history_follows_mint_transfer_redeem_and_destroy() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "creator".into(),
+                amount: Uint128::new(500),
+            };
+            execute(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(300),
+            };
+            execute(deps.as_mut(), env_at(200), mock_info("addr0000", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Redeem {
+                amount: Uint128::new(200),
+            };
+            execute(deps.as_mut(), env_at(300), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0001".into(),
+            };
+            execute(deps.as_mut(), env_at(400), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::DestroyBlockedFunds {
+                address: "addr0001".into(),
+            };
+            execute(deps.as_mut(), env_at(400), mock_info("creator", &[]), msg).unwrap();
+
+            // Values are as of the start of the given block.
+            assert_eq!(balance_at(deps.as_ref(), "addr0000", 100), Uint128::zero());
+            assert_eq!(
+                balance_at(deps.as_ref(), "addr0000", 101),
+                Uint128::new(1000)
+            );
+            assert_eq!(
+                balance_at(deps.as_ref(), "addr0000", 201),
+                Uint128::new(700)
+            );
+            assert_eq!(
+                balance_at(deps.as_ref(), "addr0001", 250),
+                Uint128::new(300)
+            );
+            assert_eq!(balance_at(deps.as_ref(), "addr0001", 401), Uint128::zero());
+            assert_eq!(balance_at(deps.as_ref(), "creator", 301), Uint128::new(300));
+
+            assert_eq!(total_supply_at(deps.as_ref(), 50), Uint128::zero());
+            assert_eq!(total_supply_at(deps.as_ref(), 150), Uint128::new(1500));
+            assert_eq!(total_supply_at(deps.as_ref(), 350), Uint128::new(1300));
+            assert_eq!(total_supply_at(deps.as_ref(), 401), Uint128::new(1000));
+            assert_eq!(
+                query_token_info(deps.as_ref()).unwrap().total_supply,
+                Uint128::new(1000)
+            );
+        }
+
+        #[test]
+        fn fees_are_snapshotted() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::UpdateFeeConfig {
+                collector: "collector".into(),
+                basis_points: 1000,
+                min_fee: Uint128::zero(),
+                max_fee: None,
+            };
+            execute(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(500),
+            };
+            execute(deps.as_mut(), env_at(200), mock_info("addr0000", &[]), msg).unwrap();
+
+            assert_eq!(
+                balance_at(deps.as_ref(), "addr0001", 201),
+                Uint128::new(450)
+            );
+            assert_eq!(
+                balance_at(deps.as_ref(), "collector", 201),
+                Uint128::new(50)
+            );
+        }
+
+        #[test]
+        fn escrow_is_snapshotted() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), env_at(100), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Hold {
+                to: "addr0001".into(),
+                amount: Uint128::new(400),
+                expires: Expiration::AtHeight(300),
+            };
+            execute(deps.as_mut(), env_at(200), mock_info("addr0000", &[]), msg).unwrap();
+            let msg = ExecuteMsg::Release { id: 0 };
+            execute(deps.as_mut(), env_at(300), mock_info("anyone", &[]), msg).unwrap();
+
+            assert_eq!(
+                balance_at(deps.as_ref(), MOCK_CONTRACT_ADDR, 201),
+                Uint128::new(400)
+            );
+            assert_eq!(
+                balance_at(deps.as_ref(), "addr0000", 201),
+                Uint128::new(600)
+            );
+            assert_eq!(
+                balance_at(deps.as_ref(), MOCK_CONTRACT_ADDR, 301),
+                Uint128::zero()
+            );
+            assert_eq!(
+                balance_at(deps.as_ref(), "addr0001", 301),
+                Uint128::new(400)
+            );
+            let stats: StatsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap())
+                    .unwrap();
+            assert_eq!(stats.holders, 2);
+        }
+    }
+
+    mod send_whitelist {
+        use super::*;
+
+        fn send_msg(contract: &str) -> ExecuteMsg {
+            ExecuteMsg::Send {
+                contract: contract.into(),
+                amount: Uint128::new(100),
+                msg: Binary::default(),
+            }
+        }
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        #[test]
+        fn blocked_contract_cannot_receive() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "badvault".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let info = mock_info("addr0000", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, send_msg("badvault")).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+
+            // The same holds when sending on behalf of someone else.
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".into(),
+                amount: Uint128::new(100),
+                expires: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+            let msg = ExecuteMsg::SendFrom {
+                owner: "addr0000".into(),
+                contract: "badvault".into(),
+                amount: Uint128::new(100),
+                msg: Binary::default(),
+            };
+            let info = mock_info("spender", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+
+            let info = mock_info("addr0000", &[]);
+            let res = execute(deps.as_mut(), mock_env(), info, send_msg("goodvault")).unwrap();
+            assert_eq!(1, res.messages.len());
+        }
+
+        #[test]
+        fn only_whitelisted_contracts_when_enabled() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let msg = ExecuteMsg::UpdateSendWhitelist { enabled: true };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::SetSendWhitelisted {
+                contract: "dex".into(),
+                whitelisted: true,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let info = mock_info("addr0000", &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, send_msg("vault")).unwrap_err();
+            assert_eq!(err, ContractError::ContractNotWhitelisted {});
+
+            let info = mock_info("addr0000", &[]);
+            execute(deps.as_mut(), mock_env(), info, send_msg("dex")).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "dex"), Uint128::new(100));
+
+            let msg = QueryMsg::SendWhitelist {
+                start_after: None,
+                limit: None,
+            };
+            let data = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let loaded: SendWhitelistResponse = from_binary(&data).unwrap();
+            assert_eq!(
+                loaded,
+                SendWhitelistResponse {
+                    enabled: true,
+                    contracts: vec!["dex".to_string()],
+                }
+            );
+
+            // Disabling the whitelist allows any unblocked contract again.
+            let msg = ExecuteMsg::UpdateSendWhitelist { enabled: false };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let info = mock_info("addr0000", &[]);
+            execute(deps.as_mut(), mock_env(), info, send_msg("vault")).unwrap();
+        }
+    }
+
+    mod addresses {
+        use super::*;
+
+        const TERRA_ADDR: &str = "terra1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8";
+        const COSMOS_ADDR: &str = "cosmos1dcegyrekltswvyy0xy69ydgxn9x8x32zdtapd8";
+
+        #[test]
+        fn mixed_case_addresses_are_rejected() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "Addr0001".into(),
+                amount: Uint128::new(100),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidAddress {
+                    address: "Addr0001".into()
+                }
+            );
+
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "AddR0000".into(),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidAddress {
+                    address: "AddR0000".into()
+                }
+            );
+        }
+
+        #[test]
+        fn allowances_share_one_key_across_cases() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "SPENDER".into(),
+                amount: Uint128::new(100),
+                expires: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+            for (owner, spender) in [("addr0000", "spender"), ("ADDR0000", "SPENDER")] {
+                let res = query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::Allowance {
+                        owner: owner.into(),
+                        spender: spender.into(),
+                    },
+                )
+                .unwrap();
+                let allowance: cw20::AllowanceResponse = from_binary(&res).unwrap();
+                assert_eq!(allowance.allowance, Uint128::new(100));
+            }
+        }
+
+        #[test]
+        fn wrong_prefix_is_rejected() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+
+            let msg = ExecuteMsg::UpdateAddressPrefix {
+                prefix: Some("terra".into()),
+            };
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("addr0000", &[]),
+                msg.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Mint {
+                recipient: TERRA_ADDR.to_uppercase(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), TERRA_ADDR), Uint128::new(1000));
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: COSMOS_ADDR.into(),
+                amount: Uint128::new(100),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info(TERRA_ADDR, &[]), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidAddressPrefix {
+                    address: COSMOS_ADDR.into(),
+                    prefix: "terra".into(),
+                }
+            );
+
+            let err = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::IsBlocked {
+                    address: COSMOS_ADDR.into(),
+                },
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains("does not use the terra prefix"));
+
+            let msg = ExecuteMsg::UpdateAddressPrefix {
+                prefix: Some("Terra".into()),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        }
+    }
+
+    mod timelock {
+        use super::*;
+        use crate::msg::{PendingActionsResponse, TimelockAction};
+
+        const DELAY: u64 = 86_400;
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.branch(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::UpdateTimelock {
+                delay: DELAY,
+                guardian: Some("guardian".into()),
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        fn schedule(deps: DepsMut, action: TimelockAction) -> u64 {
+            let msg = ExecuteMsg::Schedule {
+                action,
+                eta: mock_env().block.time.plus_seconds(DELAY),
+            };
+            let res = execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "id")
+                .unwrap()
+                .value
+                .parse()
+                .unwrap()
+        }
+
+        fn pending(deps: Deps) -> PendingActionsResponse {
+            let msg = QueryMsg::PendingActions {
+                start_after: None,
+                limit: None,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        }
+
+        fn is_blocked(deps: Deps, address: &str) -> bool {
+            let msg = QueryMsg::IsBlocked {
+                address: address.into(),
+            };
+            let res: BlockedResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.blocked
+        }
+
+        #[test]
+        fn sensitive_actions_require_scheduling() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let info = mock_info("creator", &[]);
+
+            for msg in [
+                ExecuteMsg::RemoveFromBlockedList {
+                    address: "addr0000".into(),
+                },
+                ExecuteMsg::UpdateMinter {
+                    address: "newminter".into(),
+                },
+                ExecuteMsg::UpdateTimelock {
+                    delay: 0,
+                    guardian: None,
+                },
+            ] {
+                let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+                assert_eq!(err, ContractError::TimelockRequired {});
+            }
+
+            // Blocking stays immediate.
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0001".into(),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+            assert!(is_blocked(deps.as_ref(), "addr0001"));
+
+            // Setting or lowering the cap is immediate, raising it is not.
+            let msg = ExecuteMsg::UpdateMintCap {
+                cap: Some(Uint128::new(1000)),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::UpdateMintCap {
+                cap: Some(Uint128::new(500)),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+            for cap in [Some(Uint128::new(501)), None] {
+                let msg = ExecuteMsg::UpdateMintCap { cap };
+                let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+                assert_eq!(err, ContractError::TimelockRequired {});
+            }
+            assert_eq!(
+                query_minter(deps.as_ref()).unwrap().unwrap().cap,
+                Some(Uint128::new(500))
+            );
+        }
+
+        #[test]
+        fn scheduled_action_runs_after_delay() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let msg = ExecuteMsg::Schedule {
+                action: TimelockAction::RemoveFromBlockedList {
+                    address: "addr0000".into(),
+                },
+                eta: mock_env().block.time.plus_seconds(DELAY - 1),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::EtaTooEarly {
+                    earliest: mock_env().block.time.plus_seconds(DELAY)
+                }
+            );
+
+            let id = schedule(
+                deps.as_mut(),
+                TimelockAction::RemoveFromBlockedList {
+                    address: "ADDR0000".into(),
+                },
+            );
+            let actions = pending(deps.as_ref()).actions;
+            assert_eq!(actions.len(), 1);
+            assert_eq!(
+                actions[0].action,
+                TimelockAction::RemoveFromBlockedList {
+                    address: "addr0000".into()
+                }
+            );
+
+            let msg = ExecuteMsg::ExecuteScheduled { id };
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(DELAY - 1);
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("creator", &[]),
+                msg.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::ActionNotReady {
+                    id,
+                    eta: mock_env().block.time.plus_seconds(DELAY)
+                }
+            );
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("guardian", &[]),
+                msg.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+
+            env.block.time = env.block.time.plus_seconds(1);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("creator", &[]),
+                msg.clone(),
+            )
+            .unwrap();
+            assert!(!is_blocked(deps.as_ref(), "addr0000"));
+            assert!(pending(deps.as_ref()).actions.is_empty());
+
+            // An action runs only once.
+            let err = execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::ActionNotFound { id });
+        }
+
+        #[test]
+        fn guardian_can_cancel() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let id = schedule(
+                deps.as_mut(),
+                TimelockAction::UpdateMinter {
+                    address: "newminter".into(),
+                },
+            );
+            let other = schedule(deps.as_mut(), TimelockAction::UpdateMintCap { cap: None });
+
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("addr0001", &[]),
+                ExecuteMsg::Cancel { id },
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("guardian", &[]),
+                ExecuteMsg::Cancel { id },
+            )
+            .unwrap();
+
+            let actions = pending(deps.as_ref()).actions;
+            assert_eq!(actions.len(), 1);
+            assert_eq!(actions[0].id, other);
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(DELAY);
+            let err = execute(
+                deps.as_mut(),
+                env,
+                mock_info("creator", &[]),
+                ExecuteMsg::ExecuteScheduled { id },
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::ActionNotFound { id });
+            assert_eq!(
+                query_minter(deps.as_ref()).unwrap().unwrap().minter,
+                "creator"
+            );
+        }
+
+        #[test]
+        fn minter_change_keeps_cap() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::UpdateMintCap {
+                cap: Some(Uint128::new(100)),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::UpdateTimelock {
+                delay: DELAY,
+                guardian: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let id = schedule(
+                deps.as_mut(),
+                TimelockAction::UpdateMinter {
+                    address: "treasury".into(),
+                },
+            );
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(DELAY);
+            let msg = ExecuteMsg::ExecuteScheduled { id };
+            execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap();
+
+            let minter = query_minter(deps.as_ref()).unwrap().unwrap();
+            assert_eq!(minter.minter, "treasury");
+            assert_eq!(minter.cap, Some(Uint128::new(100)));
+        }
+    }
+
+    mod approvals {
+        use super::*;
+        use crate::msg::{
+            ProposalAction, ProposalResponse, ProposalStatus, ProposalVotesResponse,
+            ProposalsResponse, StatsResponse, VoteInfo,
+        };
+
+        const PERIOD: u64 = 3_600;
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.branch(), mock_env(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.branch(), mock_env(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::UpdateApprovers {
+                approvers: vec!["alice".into(), "bob".into(), "carol".into()],
+                threshold: 2,
+                voting_period: PERIOD,
+            };
+            execute(deps, mock_env(), info, msg).unwrap();
+        }
+
+        fn propose(deps: DepsMut, proposer: &str, action: ProposalAction) -> u64 {
+            let res = execute(
+                deps,
+                mock_env(),
+                mock_info(proposer, &[]),
+                ExecuteMsg::Propose { action },
+            )
+            .unwrap();
+            res.attributes
+                .iter()
+                .find(|attr| attr.key == "proposal_id")
+                .unwrap()
+                .value
+                .parse()
+                .unwrap()
+        }
+
+        fn vote(
+            deps: DepsMut,
+            env: Env,
+            voter: &str,
+            proposal_id: u64,
+            approve: bool,
+        ) -> Result<Response, ContractError> {
+            execute(
+                deps,
+                env,
+                mock_info(voter, &[]),
+                ExecuteMsg::Vote {
+                    proposal_id,
+                    approve,
+                },
+            )
+        }
+
+        fn proposal(deps: Deps, env: Env, proposal_id: u64) -> ProposalResponse {
+            from_binary(&query(deps, env, QueryMsg::Proposal { proposal_id }).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn minter_cannot_act_alone() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let info = mock_info("creator", &[]);
+
+            for msg in [
+                ExecuteMsg::DestroyBlockedFunds {
+                    address: "addr0000".into(),
+                },
+                ExecuteMsg::UpdateMinter {
+                    address: "alice".into(),
+                },
+                ExecuteMsg::UpdateApprovers {
+                    approvers: vec![],
+                    threshold: 0,
+                    voting_period: 0,
+                },
+            ] {
+                let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+                assert_eq!(err, ContractError::ApprovalRequired {});
+            }
+
+            // Only approvers may propose.
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                ExecuteMsg::Propose {
+                    action: ProposalAction::UpdateMinter {
+                        address: "alice".into(),
+                    },
+                },
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+        }
+
+        #[test]
+        fn seizure_runs_at_threshold() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let id = propose(
+                deps.as_mut(),
+                "alice",
+                ProposalAction::DestroyBlockedFunds {
+                    address: "ADDR0000".into(),
+                },
+            );
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(1000));
+
+            let open: ProposalsResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::OpenProposals {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(open.proposals.len(), 1);
+            assert_eq!(
+                open.proposals[0].action,
+                ProposalAction::DestroyBlockedFunds {
+                    address: "addr0000".into()
+                }
+            );
+            assert_eq!(open.proposals[0].approvals, 1);
+
+            let err = vote(deps.as_mut(), mock_env(), "alice", id, true).unwrap_err();
+            assert_eq!(err, ContractError::AlreadyVoted {});
+            let err = vote(deps.as_mut(), mock_env(), "creator", id, true).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+
+            vote(deps.as_mut(), mock_env(), "bob", id, true).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::zero());
+            assert_eq!(
+                query_token_info(deps.as_ref()).unwrap().total_supply,
+                Uint128::zero()
+            );
+            assert_eq!(
+                proposal(deps.as_ref(), mock_env(), id).status,
+                ProposalStatus::Executed
+            );
+
+            let votes: ProposalVotesResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::ProposalVotes {
+                        proposal_id: id,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(
+                votes.votes,
+                vec![
+                    VoteInfo {
+                        voter: "alice".into(),
+                        approve: true
+                    },
+                    VoteInfo {
+                        voter: "bob".into(),
+                        approve: true
+                    },
+                ]
+            );
+
+            let err = vote(deps.as_mut(), mock_env(), "carol", id, true).unwrap_err();
+            assert_eq!(err, ContractError::ProposalClosed { id });
+        }
+
+        #[test]
+        fn proposals_can_be_rejected_or_expire() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let rejected = propose(
+                deps.as_mut(),
+                "alice",
+                ProposalAction::UpdateMinter {
+                    address: "alice".into(),
+                },
+            );
+            vote(deps.as_mut(), mock_env(), "bob", rejected, false).unwrap();
+            assert_eq!(
+                proposal(deps.as_ref(), mock_env(), rejected).status,
+                ProposalStatus::Open
+            );
+            vote(deps.as_mut(), mock_env(), "carol", rejected, false).unwrap();
+            assert_eq!(
+                proposal(deps.as_ref(), mock_env(), rejected).status,
+                ProposalStatus::Rejected
+            );
+
+            let expiring = propose(
+                deps.as_mut(),
+                "bob",
+                ProposalAction::UpdateMinter {
+                    address: "bob".into(),
+                },
+            );
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(PERIOD);
+            assert_eq!(
+                proposal(deps.as_ref(), env.clone(), expiring).status,
+                ProposalStatus::Expired
+            );
+            let err = vote(deps.as_mut(), env, "carol", expiring, true).unwrap_err();
+            assert_eq!(err, ContractError::ProposalClosed { id: expiring });
+            assert_eq!(
+                query_minter(deps.as_ref()).unwrap().unwrap().minter,
+                "creator"
+            );
+        }
+
+        #[test]
+        fn approved_minter_change_waits_for_timelock() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let msg = ExecuteMsg::UpdateTimelock {
+                delay: 100,
+                guardian: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            // Scheduling directly would skip the approvers.
+            let msg = ExecuteMsg::Schedule {
+                action: TimelockAction::UpdateMinter {
+                    address: "alice".into(),
+                },
+                eta: mock_env().block.time.plus_seconds(100),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::ApprovalRequired {});
+
+            let id = propose(
+                deps.as_mut(),
+                "alice",
+                ProposalAction::UpdateMinter {
+                    address: "alice".into(),
+                },
+            );
+            vote(deps.as_mut(), mock_env(), "carol", id, true).unwrap();
+            assert_eq!(
+                query_minter(deps.as_ref()).unwrap().unwrap().minter,
+                "creator"
+            );
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(100);
+            execute(
+                deps.as_mut(),
+                env,
+                mock_info("creator", &[]),
+                ExecuteMsg::ExecuteScheduled { id: 0 },
+            )
+            .unwrap();
+            assert_eq!(
+                query_minter(deps.as_ref()).unwrap().unwrap().minter,
+                "alice"
+            );
+        }
+
+        #[test]
+        fn minter_change_scheduled_earlier_needs_approval() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::UpdateTimelock {
+                delay: 100,
+                guardian: None,
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::Schedule {
+                action: TimelockAction::UpdateMinter {
+                    address: "mallory".into(),
+                },
+                eta: mock_env().block.time.plus_seconds(100),
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::UpdateApprovers {
+                approvers: vec!["alice".into(), "bob".into(), "carol".into()],
+                threshold: 2,
+                voting_period: PERIOD,
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(100);
+            let msg = ExecuteMsg::ExecuteScheduled { id: 0 };
+            let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(err, ContractError::ApprovalRequired {});
+            assert_eq!(
+                query_minter(deps.as_ref()).unwrap().unwrap().minter,
+                "creator"
+            );
+        }
+
+        #[test]
+        fn open_proposals_skip_closed_ones() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let open = |deps: Deps, env: Env, start_after: Option<u64>| -> Vec<u64> {
+                let msg = QueryMsg::OpenProposals {
+                    start_after,
+                    limit: Some(1),
+                };
+                let res: ProposalsResponse = from_binary(&query(deps, env, msg).unwrap()).unwrap();
+                res.proposals.iter().map(|proposal| proposal.id).collect()
+            };
+
+            let minter = |address: &str| ProposalAction::UpdateMinter {
+                address: address.into(),
+            };
+            let rejected = propose(deps.as_mut(), "alice", minter("alice"));
+            vote(deps.as_mut(), mock_env(), "bob", rejected, false).unwrap();
+            vote(deps.as_mut(), mock_env(), "carol", rejected, false).unwrap();
+            let mut later = mock_env();
+            later.block.time = later.block.time.plus_seconds(10);
+            let first = propose(deps.as_mut(), "bob", minter("bob"));
+            let id = propose(deps.as_mut(), "carol", minter("carol"));
+            let msg = ExecuteMsg::Propose {
+                action: minter("dave"),
+            };
+            execute(deps.as_mut(), later.clone(), mock_info("alice", &[]), msg).unwrap();
+            let last = id + 1;
+
+            assert_eq!(open(deps.as_ref(), mock_env(), None), vec![first]);
+            assert_eq!(open(deps.as_ref(), mock_env(), Some(first)), vec![id]);
+            assert_eq!(open(deps.as_ref(), mock_env(), Some(id)), vec![last]);
+            assert_eq!(
+                open(deps.as_ref(), mock_env(), Some(last)),
+                Vec::<u64>::new()
+            );
+
+            // Once the earlier proposals expire only the one opened later is left.
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(PERIOD);
+            assert_eq!(open(deps.as_ref(), env, None), vec![last]);
+        }
+
+        #[test]
+        fn controller_needs_approval() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let info = mock_info("creator", &[]);
+            let grant = |role| ExecuteMsg::SetRole {
+                role,
+                address: "creator".into(),
+                granted: true,
+            };
+            let force = ExecuteMsg::ForceTransfer {
+                from: "addr0000".into(),
+                to: "creator".into(),
+                amount: Uint128::new(1000),
+                legal_reference: "case 2024-117".into(),
+            };
+
+            // The minter can't make itself a controller and seize funds alone.
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                grant(Role::Controller),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::ApprovalRequired {});
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), force.clone()).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+            // Other roles are still granted directly.
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                grant(Role::Attestor),
+            )
+            .unwrap();
+
+            let id = propose(
+                deps.as_mut(),
+                "alice",
+                ProposalAction::SetRole {
+                    role: Role::Controller,
+                    address: "creator".into(),
+                    granted: true,
+                },
+            );
+            vote(deps.as_mut(), mock_env(), "bob", id, true).unwrap();
+            execute(deps.as_mut(), mock_env(), info, force).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "creator"), Uint128::new(1000));
+        }
+
+        #[test]
+        fn recovery_needs_approval() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let msg = ExecuteMsg::RecoverAccount {
+                old: "addr0000".into(),
+                new: "new00000".into(),
+                reference: "ticket 4411".into(),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::ApprovalRequired {});
+            assert_eq!(get_balance(deps.as_ref(), "new00000"), Uint128::zero());
+
+            let id = propose(
+                deps.as_mut(),
+                "alice",
+                ProposalAction::RecoverAccount {
+                    old: "addr0000".into(),
+                    new: "new00000".into(),
+                    reference: "ticket 4411".into(),
+                },
+            );
+            vote(deps.as_mut(), mock_env(), "bob", id, true).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::zero());
+            assert_eq!(get_balance(deps.as_ref(), "new00000"), Uint128::new(1000));
+            let stats: StatsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap())
+                    .unwrap();
+            assert_eq!(stats.blocked_balance, Uint128::zero());
+        }
+    }
+
+    mod reviews {
+        use super::*;
+        use crate::msg::{ReviewDecision, ReviewStatus, ReviewsResponse, Role};
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let info = mock_info("creator", &[]);
+            for address in ["addr0000", "addr0001"] {
+                let msg = ExecuteMsg::AddToBlockedList {
+                    address: address.into(),
+                };
+                execute(deps.branch(), mock_env(), info.clone(), msg).unwrap();
+            }
+            let msg = ExecuteMsg::SetRole {
+                role: Role::Compliance,
+                address: "officer".into(),
+                granted: true,
+            };
+            execute(deps, mock_env(), info, msg).unwrap();
+        }
+
+        fn request(deps: DepsMut, sender: &str) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::RequestReview {
+                evidence_uri: "ipfs://evidence".into(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        }
+
+        fn resolve(
+            deps: DepsMut,
+            sender: &str,
+            address: &str,
+            decision: ReviewDecision,
+        ) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::ResolveReview {
+                address: address.into(),
+                decision,
+                note: "checked".into(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        }
+
+        fn by_status(deps: Deps, status: ReviewStatus) -> ReviewsResponse {
+            let msg = QueryMsg::ReviewsByStatus {
+                status,
+                start_after: None,
+                limit: None,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        }
+
+        fn is_blocked(deps: Deps, address: &str) -> bool {
+            let msg = QueryMsg::IsBlocked {
+                address: address.into(),
+            };
+            let res: BlockedResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.blocked
+        }
+
+        #[test]
+        fn only_blocked_addresses_can_request() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let err = request(deps.as_mut(), "addr0002").unwrap_err();
+            assert_eq!(err, ContractError::NotBlocked {});
+
+            let msg = ExecuteMsg::RequestReview {
+                evidence_uri: " ".into(),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidEvidenceUri {});
+
+            request(deps.as_mut(), "addr0000").unwrap();
+            let err = request(deps.as_mut(), "addr0000").unwrap_err();
+            assert_eq!(err, ContractError::ReviewPending {});
+        }
+
+        #[test]
+        fn compliance_resolves_reviews() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            request(deps.as_mut(), "addr0000").unwrap();
+            request(deps.as_mut(), "addr0001").unwrap();
+            assert_eq!(
+                by_status(deps.as_ref(), ReviewStatus::Pending)
+                    .reviews
+                    .len(),
+                2
+            );
+
+            let err = resolve(
+                deps.as_mut(),
+                "creator",
+                "addr0000",
+                ReviewDecision::Unblock,
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+
+            resolve(
+                deps.as_mut(),
+                "officer",
+                "addr0000",
+                ReviewDecision::Unblock,
+            )
+            .unwrap();
+            resolve(deps.as_mut(), "officer", "addr0001", ReviewDecision::Uphold).unwrap();
+            assert!(!is_blocked(deps.as_ref(), "addr0000"));
+            assert!(is_blocked(deps.as_ref(), "addr0001"));
+
+            let err = resolve(
+                deps.as_mut(),
+                "officer",
+                "addr0001",
+                ReviewDecision::Unblock,
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::NoPendingReview {});
+
+            assert!(by_status(deps.as_ref(), ReviewStatus::Pending)
+                .reviews
+                .is_empty());
+            let upheld = by_status(deps.as_ref(), ReviewStatus::Upheld).reviews;
+            assert_eq!(upheld.len(), 1);
+            assert_eq!(upheld[0].address, "addr0001");
+            assert_eq!(upheld[0].resolved_by.as_deref(), Some("officer"));
+            assert_eq!(upheld[0].note.as_deref(), Some("checked"));
+
+            // An upheld block can be appealed again; the history is kept.
+            request(deps.as_mut(), "addr0001").unwrap();
+            let msg = QueryMsg::Reviews {
+                address: "addr0001".into(),
+                start_after: None,
+                limit: None,
+            };
+            let history: ReviewsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            let statuses: Vec<_> = history.reviews.iter().map(|review| review.status).collect();
+            assert_eq!(statuses, vec![ReviewStatus::Upheld, ReviewStatus::Pending]);
+        }
+
+        #[test]
+        fn unblock_decision_respects_timelock() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let msg = ExecuteMsg::UpdateTimelock {
+                delay: 100,
+                guardian: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            request(deps.as_mut(), "addr0000").unwrap();
+            resolve(
+                deps.as_mut(),
+                "officer",
+                "addr0000",
+                ReviewDecision::Unblock,
+            )
+            .unwrap();
+            assert!(is_blocked(deps.as_ref(), "addr0000"));
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(100);
+            let msg = ExecuteMsg::ExecuteScheduled { id: 0 };
+            execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap();
+            assert!(!is_blocked(deps.as_ref(), "addr0000"));
+        }
+    }
+
+    mod stats {
+        use super::*;
+        use crate::msg::StatsResponse;
+
+        fn stats(deps: Deps) -> StatsResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::Stats {}).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn totals_follow_supply_and_blocklist_changes() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let minter = mock_info("creator", &[]);
+
+            for (recipient, amount) in [("addr0000", 600u128), ("addr0001", 400), ("creator", 100)]
+            {
+                let msg = ExecuteMsg::Mint {
+                    recipient: recipient.into(),
+                    amount: Uint128::new(amount),
+                };
+                execute(deps.as_mut(), mock_env(), minter.clone(), msg).unwrap();
+            }
+            let msg = ExecuteMsg::Redeem {
+                amount: Uint128::new(100),
+            };
+            execute(deps.as_mut(), mock_env(), minter.clone(), msg).unwrap();
+
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), minter.clone(), msg.clone()).unwrap();
+            // Blocking twice counts once.
+            execute(deps.as_mut(), mock_env(), minter.clone(), msg).unwrap();
+
+            // Incoming transfers raise the blocked balance.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(150),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0001", &[]), msg).unwrap();
+
+            assert_eq!(
+                stats(deps.as_ref()),
+                StatsResponse {
+                    total_supply: Uint128::new(1000),
+                    holders: 2,
+                    blocked_addresses: 1,
+                    blocked_balance: Uint128::new(750),
+                    destroyed: Uint128::zero(),
+                    minted: Uint128::new(1100),
+                    redeemed: Uint128::new(100),
+                }
+            );
+
+            let msg = ExecuteMsg::DestroyBlockedFunds {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), minter.clone(), msg).unwrap();
+            let msg = ExecuteMsg::RemoveFromBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), minter, msg).unwrap();
+
+            let res = stats(deps.as_ref());
+            assert_eq!(res.total_supply, Uint128::new(250));
+            assert_eq!(res.holders, 1);
+            assert_eq!(res.blocked_addresses, 0);
+            assert_eq!(res.blocked_balance, Uint128::zero());
+            assert_eq!(res.destroyed, Uint128::new(750));
+        }
+
+        #[test]
+        fn migrate_rebuilds_totals() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            // State written before the running totals existed.
+            for (address, amount) in [("addr0000", 600u128), ("addr0001", 400)] {
+                BALANCES
+                    .save(
+                        deps.as_mut().storage,
+                        &Addr::unchecked(address),
+                        &Uint128::new(amount),
+                    )
+                    .unwrap();
+            }
+            BLOCKED
+                .save(deps.as_mut().storage, &Addr::unchecked("addr0001"), &true)
+                .unwrap();
+
+            migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+            let res = stats(deps.as_ref());
+            assert_eq!(res.holders, 2);
+            assert_eq!(res.blocked_addresses, 1);
+            assert_eq!(res.blocked_balance, Uint128::new(400));
+
+            // Later changes apply on top of the rebuilt totals.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(600),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+            let res = stats(deps.as_ref());
+            assert_eq!(res.holders, 1);
+            assert_eq!(res.blocked_balance, Uint128::new(1000));
+        }
+    }
+
+    mod account_status {
+        use super::*;
+        use crate::msg::{AccountStatusResponse, BlockRecord, ReceivedAllowance};
+        use cw20::Expiration;
+
+        fn status(deps: Deps, address: &str) -> AccountStatusResponse {
+            let msg = QueryMsg::AccountStatus {
+                address: address.into(),
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn reports_block_and_allowances() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            for (owner, spender, amount) in [
+                ("addr0000", "spender", 300u128),
+                ("addr0001", "addr0000", 50),
+                ("addr0002", "addr0000", 20),
+            ] {
+                let msg = ExecuteMsg::IncreaseAllowance {
+                    spender: spender.into(),
+                    amount: Uint128::new(amount),
+                    expires: None,
+                };
+                execute(deps.as_mut(), mock_env(), mock_info(owner, &[]), msg).unwrap();
+            }
+            // A withdrawn allowance no longer shows up.
+            let msg = ExecuteMsg::DecreaseAllowance {
+                spender: "addr0000".into(),
+                amount: Uint128::new(20),
+                expires: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0002", &[]), msg).unwrap();
+
+            let res = status(deps.as_ref(), "ADDR0000");
+            assert_eq!(res.balance, Uint128::new(1000));
+            assert!(!res.blocked);
+            assert_eq!(res.block_record, None);
+            assert_eq!(res.frozen, Uint128::zero());
+            assert_eq!(
+                res.allowances_granted,
+                vec![AllowanceInfo {
+                    spender: "spender".into(),
+                    allowance: Uint128::new(300),
+                    expires: Expiration::Never {},
+                }]
+            );
+            assert_eq!(
+                res.allowances_received,
+                vec![ReceivedAllowance {
+                    owner: "addr0001".into(),
+                    allowance: Uint128::new(50),
+                    expires: Expiration::Never {},
+                }]
+            );
+
+            let mut env = mock_env();
+            env.block.height += 5;
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+            let res = status(deps.as_ref(), "addr0000");
+            assert!(res.blocked);
+            assert_eq!(
+                res.block_record,
+                Some(BlockRecord {
+                    blocked_at: env.block.time,
+                    height: env.block.height,
+                    recovered_to: None,
+                    recovery_reference: None,
+                    origin: None,
+                })
+            );
+            assert_eq!(res.frozen, Uint128::new(1000));
+
+            let msg = ExecuteMsg::RemoveFromBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let res = status(deps.as_ref(), "addr0000");
+            assert_eq!(res.block_record, None);
+            assert_eq!(res.frozen, Uint128::zero());
+        }
+
+        #[test]
+        fn skips_expired_allowances() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let expires = Expiration::AtHeight(mock_env().block.height + 10);
+            for (owner, spender) in [("addr0000", "spender"), ("addr0001", "addr0000")] {
+                let msg = ExecuteMsg::IncreaseAllowance {
+                    spender: spender.into(),
+                    amount: Uint128::new(100),
+                    expires: Some(expires),
+                };
+                execute(deps.as_mut(), mock_env(), mock_info(owner, &[]), msg).unwrap();
+            }
+            let res = status(deps.as_ref(), "addr0000");
+            assert_eq!(res.allowances_granted.len(), 1);
+            assert_eq!(res.allowances_received.len(), 1);
+
+            let mut env = mock_env();
+            env.block.height += 10;
+            let msg = QueryMsg::AccountStatus {
+                address: "addr0000".into(),
+            };
+            let res: AccountStatusResponse =
+                from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+            assert!(res.allowances_granted.is_empty());
+            assert!(res.allowances_received.is_empty());
+        }
+    }
+
+    mod can_transfer {
+        use super::*;
+        use crate::msg::{CanTransferResponse, Restriction};
+        use cw20::Expiration;
+
+        fn can_transfer(
+            deps: Deps,
+            from: &str,
+            to: &str,
+            amount: u128,
+            spender: Option<&str>,
+        ) -> Option<Restriction> {
+            let msg = QueryMsg::CanTransfer {
+                from: from.into(),
+                to: to.into(),
+                amount: Uint128::new(amount),
+                spender: spender.map(Into::into),
+            };
+            let res: CanTransferResponse =
+                from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.allowed, res.reason.is_none());
+            res.reason
+        }
+
+        #[test]
+        fn reports_the_first_failing_check() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let minter = mock_info("creator", &[]);
+            for msg in [
+                ExecuteMsg::Mint {
+                    recipient: "addr0000".into(),
+                    amount: Uint128::new(1000),
+                },
+                ExecuteMsg::Mint {
+                    recipient: "addr0001".into(),
+                    amount: Uint128::new(100),
+                },
+                ExecuteMsg::UpdateMaxBalance {
+                    max_balance: Some(Uint128::new(500)),
+                },
+            ] {
+                execute(deps.as_mut(), mock_env(), minter.clone(), msg).unwrap();
+            }
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".into(),
+                amount: Uint128::new(200),
+                expires: Some(Expiration::AtHeight(mock_env().block.height + 1)),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0000", "addr0002", 400, None),
+                None
+            );
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0000", "addr0002", 0, None),
+                Some(Restriction::ZeroAmount)
+            );
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0000", "addr0002", 600, None),
+                Some(Restriction::MaxBalanceExceeded)
+            );
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0001", "addr0002", 101, None),
+                Some(Restriction::InsufficientBalance)
+            );
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0000", "addr0002", 150, Some("spender")),
+                None
+            );
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0000", "addr0002", 250, Some("spender")),
+                Some(Restriction::InsufficientAllowance)
+            );
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0001", "addr0002", 10, Some("spender")),
+                Some(Restriction::NoAllowance)
+            );
+
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), minter, msg).unwrap();
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0000", "addr0002", 10, Some("spender")),
+                Some(Restriction::Blocked)
+            );
+
+            // The query agrees with what the transfer actually does.
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0002".into(),
+                amount: Uint128::new(10),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err.restriction(), Some(Restriction::Blocked));
+        }
+
+        #[test]
+        fn expired_allowance_is_reported() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".into(),
+                amount: Uint128::new(200),
+                expires: Some(Expiration::AtTime(mock_env().block.time)),
+            };
+            let mut env = mock_env();
+            env.block.time = env.block.time.minus_seconds(1);
+            execute(deps.as_mut(), env, mock_info("addr0000", &[]), msg).unwrap();
+            assert_eq!(
+                can_transfer(deps.as_ref(), "addr0000", "addr0002", 10, Some("spender")),
+                Some(Restriction::AllowanceExpired)
+            );
+        }
+    }
+
+    mod restriction_codes {
+        use super::*;
+        use crate::msg::{Restriction, RestrictionMessageResponse};
+
+        fn message(deps: Deps, code: u8) -> StdResult<RestrictionMessageResponse> {
+            let res = query(deps, mock_env(), QueryMsg::RestrictionMessage { code })?;
+            from_binary(&res)
+        }
+
+        #[test]
+        fn codes_resolve_to_messages() {
+            let deps = mock_dependencies();
+            assert_eq!(
+                message(deps.as_ref(), 0).unwrap(),
+                RestrictionMessageResponse {
+                    code: 0,
+                    restriction: None,
+                    message: "No restriction".into(),
+                }
+            );
+            assert_eq!(
+                message(deps.as_ref(), 1).unwrap(),
+                RestrictionMessageResponse {
+                    code: 1,
+                    restriction: Some(Restriction::Blocked),
+                    message: "Address is on the blocklist".into(),
+                }
+            );
+            message(deps.as_ref(), 99).unwrap_err();
+        }
+
+        #[test]
+        fn transfers_report_codes() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(100),
+            };
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("addr0000", &[]),
+                msg.clone(),
+            )
+            .unwrap();
+            assert!(res.attributes.contains(&attr("restriction_code", "0")));
+
+            let block = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), block).unwrap();
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err.restriction().map(|r| r.code()), Some(1));
+            assert!(err.to_string().ends_with("(restriction 1)"));
+        }
+
+        #[test]
+        fn shortfalls_report_codes() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender".into(),
+                amount: Uint128::new(100),
+                expires: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(1001),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InsufficientBalance {});
+            assert!(err.to_string().ends_with("(restriction 3)"));
+
+            let msg = ExecuteMsg::TransferFrom {
+                owner: "addr0000".into(),
+                recipient: "addr0001".into(),
+                amount: Uint128::new(101),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("spender", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InsufficientAllowance {});
+            assert!(err.to_string().ends_with("(restriction 6)"));
+        }
+    }
+
+    mod force_transfer {
+        use super::*;
+        use crate::msg::{Role, StatsResponse, VestingSchedule};
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let info = mock_info("creator", &[]);
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.branch(), mock_env(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.branch(), mock_env(), info.clone(), msg).unwrap();
+            let msg = ExecuteMsg::SetRole {
+                role: Role::Controller,
+                address: "court".into(),
+                granted: true,
+            };
+            execute(deps, mock_env(), info, msg).unwrap();
+        }
+
+        fn force(deps: DepsMut, sender: &str, amount: u128) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::ForceTransfer {
+                from: "addr0000".into(),
+                to: "addr0001".into(),
+                amount: Uint128::new(amount),
+                legal_reference: "case 2024-117".into(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        }
+
+        #[test]
+        fn controller_moves_blocked_funds() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let err = force(deps.as_mut(), "creator", 400).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+
+            let res = force(deps.as_mut(), "court", 400).unwrap();
+            assert_eq!(res.events.len(), 1);
+            let event = &res.events[0];
+            assert_eq!(event.ty, "controller_transfer");
+            assert!(event.attributes.contains(&attr("from", "addr0000")));
+            assert!(event.attributes.contains(&attr("to", "addr0001")));
+            assert!(event.attributes.contains(&attr("amount", "400")));
+            assert!(event.attributes.contains(&attr("by", "court")));
+            assert!(event
+                .attributes
+                .contains(&attr("legal_reference", "case 2024-117")));
+
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(600));
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(400));
+            let stats: StatsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap())
+                    .unwrap();
+            assert_eq!(stats.blocked_balance, Uint128::new(600));
+            assert_eq!(stats.holders, 2);
+            assert_eq!(stats.total_supply, Uint128::new(1000));
+
+            // The balance can't go negative.
+            let err = force(deps.as_mut(), "court", 601).unwrap_err();
+            assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+        }
+
+        #[test]
+        fn validates_request() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let err = force(deps.as_mut(), "court", 0).unwrap_err();
+            assert_eq!(err, ContractError::InvalidZeroAmount {});
+
+            let msg = ExecuteMsg::ForceTransfer {
+                from: "addr0000".into(),
+                to: "addr0001".into(),
+                amount: Uint128::new(1),
+                legal_reference: "  ".into(),
+            };
+            let err = execute(deps.as_mut(), mock_env(), mock_info("court", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidLegalReference {});
+
+            let msg = ExecuteMsg::UpdateMaxBalance {
+                max_balance: Some(Uint128::new(300)),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let err = force(deps.as_mut(), "court", 400).unwrap_err();
+            assert_eq!(err, ContractError::MaxBalanceExceeded {});
+        }
+
+        #[test]
+        fn leaves_locked_tokens() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let start = mock_env().block.time;
+            let msg = ExecuteMsg::MintVested {
+                recipient: "addr0002".into(),
+                amount: Uint128::new(1000),
+                schedule: VestingSchedule::Linear {
+                    start,
+                    end: start.plus_seconds(1000),
+                    cliff: None,
+                },
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let mut env = mock_env();
+            env.block.time = start.plus_seconds(400);
+            let force = |amount: u128| ExecuteMsg::ForceTransfer {
+                from: "addr0002".into(),
+                to: "addr0001".into(),
+                amount: Uint128::new(amount),
+                legal_reference: "case 2024-117".into(),
+            };
+            let info = mock_info("court", &[]);
+            let err = execute(deps.as_mut(), env.clone(), info.clone(), force(401)).unwrap_err();
+            assert_eq!(err, ContractError::TokensLocked {});
+            execute(deps.as_mut(), env, info, force(400)).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0002"), Uint128::new(600));
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(400));
+        }
+    }
+
+    mod recovery {
+        use super::*;
+        use crate::msg::{AccountStatusResponse, BlockedResponse, StatsResponse};
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let msg = ExecuteMsg::Mint {
+                recipient: "lost0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.branch(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            for (spender, amount) in [("spender0", 300u128), ("spender1", 50), ("new00000", 10)] {
+                let msg = ExecuteMsg::IncreaseAllowance {
+                    spender: spender.into(),
+                    amount: Uint128::new(amount),
+                    expires: None,
+                };
+                execute(deps.branch(), mock_env(), mock_info("lost0000", &[]), msg).unwrap();
+            }
+        }
+
+        fn recover(deps: DepsMut, sender: &str) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::RecoverAccount {
+                old: "lost0000".into(),
+                new: "new00000".into(),
+                reference: "ticket 4411".into(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        }
+
+        fn allowance(deps: Deps, owner: &str, spender: &str) -> Uint128 {
+            query_allowance(deps, owner.into(), spender.into())
+                .unwrap()
+                .allowance
+        }
+
+        #[test]
+        fn moves_balance_and_allowances() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            // An allowance the new address already granted is topped up.
+            let msg = ExecuteMsg::IncreaseAllowance {
+                spender: "spender1".into(),
+                amount: Uint128::new(5),
+                expires: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("new00000", &[]), msg).unwrap();
+
+            let err = recover(deps.as_mut(), "spender0").unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+            recover(deps.as_mut(), "creator").unwrap();
+
+            assert_eq!(get_balance(deps.as_ref(), "lost0000"), Uint128::zero());
+            assert_eq!(get_balance(deps.as_ref(), "new00000"), Uint128::new(1000));
+            assert_eq!(
+                allowance(deps.as_ref(), "lost0000", "spender0"),
+                Uint128::zero()
+            );
+            assert_eq!(
+                allowance(deps.as_ref(), "new00000", "spender0"),
+                Uint128::new(300)
+            );
+            assert_eq!(
+                allowance(deps.as_ref(), "new00000", "spender1"),
+                Uint128::new(55)
+            );
+            // An allowance to the new address itself is dropped.
+            assert_eq!(
+                allowance(deps.as_ref(), "new00000", "new00000"),
+                Uint128::zero()
+            );
+
+            let res: BlockedResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::IsBlocked {
+                        address: "lost0000".into(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(
+                res,
+                BlockedResponse {
+                    blocked: true,
+                    recovered_to: Some("new00000".into()),
+                }
+            );
+
+            let status: AccountStatusResponse = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::AccountStatus {
+                        address: "lost0000".into(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            let record = status.block_record.unwrap();
+            assert_eq!(record.recovered_to, Some("new00000".into()));
+            assert_eq!(record.recovery_reference, Some("ticket 4411".into()));
+            assert!(status.allowances_granted.is_empty());
+
+            let stats: StatsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap())
+                    .unwrap();
+            assert_eq!(stats.total_supply, Uint128::new(1000));
+            assert_eq!(stats.holders, 1);
+            assert_eq!(stats.blocked_addresses, 1);
+            assert_eq!(stats.blocked_balance, Uint128::zero());
+
+            let err = recover(deps.as_mut(), "creator").unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::AlreadyRecovered {
+                    replacement: "new00000".into()
+                }
+            );
+        }
+
+        #[test]
+        fn unblocking_keeps_replacement() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            recover(deps.as_mut(), "creator").unwrap();
+            let is_blocked = |deps: Deps| -> BlockedResponse {
+                let msg = QueryMsg::IsBlocked {
+                    address: "lost0000".into(),
+                };
+                from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+            };
+
+            let msg = ExecuteMsg::RemoveFromBlockedList {
+                address: "lost0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            assert_eq!(
+                is_blocked(deps.as_ref()),
+                BlockedResponse {
+                    blocked: false,
+                    recovered_to: Some("new00000".into()),
+                }
+            );
+            let err = recover(deps.as_mut(), "creator").unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::AlreadyRecovered {
+                    replacement: "new00000".into()
+                }
+            );
+
+            let mut env = mock_env();
+            env.block.height += 5;
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "lost0000".into(),
+            };
+            execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+            let msg = QueryMsg::AccountStatus {
+                address: "lost0000".into(),
+            };
+            let status: AccountStatusResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            let record = status.block_record.unwrap();
+            assert_eq!(record.height, env.block.height);
+            assert_eq!(record.recovered_to, Some("new00000".into()));
+            assert_eq!(record.recovery_reference, Some("ticket 4411".into()));
+        }
+
+        #[test]
+        fn refuses_blocked_replacement() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "new00000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let err = recover(deps.as_mut(), "creator").unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+            assert_eq!(get_balance(deps.as_ref(), "lost0000"), Uint128::new(1000));
+        }
+    }
+
+    mod wrapping {
+        use super::*;
+        use crate::msg::{ReservesResponse, StatsResponse};
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+        use cosmwasm_std::{coin, coins, BankMsg, CosmosMsg};
+        use cw0::PaymentError;
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let msg = ExecuteMsg::UpdateWrapDenom {
+                denom: Some("uusd".into()),
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        fn reserves(deps: Deps) -> ReservesResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::Reserves {}).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn wrap_and_unwrap() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            let info = mock_info("addr0000", &coins(500, "uusd"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Wrap {}).unwrap();
+            deps.querier
+                .update_balance(MOCK_CONTRACT_ADDR, coins(500, "uusd"));
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(500));
+            assert_eq!(
+                reserves(deps.as_ref()),
+                ReservesResponse {
+                    denom: Some("uusd".into()),
+                    wrapped: Uint128::new(500),
+                    balance: Uint128::new(500),
+                }
+            );
+
+            let msg = ExecuteMsg::Unwrap {
+                amount: Uint128::new(200),
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+            assert_eq!(
+                res.messages[0].msg,
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "addr0000".into(),
+                    amount: coins(200, "uusd"),
+                })
+            );
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(300));
+            assert_eq!(reserves(deps.as_ref()).wrapped, Uint128::new(300));
+            assert_eq!(
+                query_token_info(deps.as_ref()).unwrap().total_supply,
+                Uint128::new(300)
+            );
+            let stats: StatsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stats {}).unwrap())
+                    .unwrap();
+            assert_eq!(stats.minted, Uint128::new(500));
+            assert_eq!(stats.redeemed, Uint128::new(200));
+
+            // Minted tokens aren't backed and can't be unwrapped beyond the reserves.
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let msg = ExecuteMsg::Unwrap {
+                amount: Uint128::new(301),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InsufficientReserves {});
+
+            // The denom is locked while reserves are owed.
+            let msg = ExecuteMsg::UpdateWrapDenom { denom: None };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::ReservesOutstanding {});
+        }
+
+        #[test]
+        fn refuses_bad_payments_and_blocked_addresses() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let info = mock_info("addr0000", &coins(500, "uusd"));
+            let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Wrap {}).unwrap_err();
+            assert_eq!(err, ContractError::WrapDisabled {});
+
+            let msg = ExecuteMsg::UpdateWrapDenom {
+                denom: Some("uusd".into()),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let info = mock_info("addr0000", &[coin(500, "uusd"), coin(1, "uluna")]);
+            let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Wrap {}).unwrap_err();
+            assert_eq!(err, ContractError::Payment(PaymentError::MultipleDenoms {}));
+            let info = mock_info("addr0000", &coins(500, "uluna"));
+            let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Wrap {}).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::Payment(PaymentError::MissingDenom("uusd".into()))
+            );
+
+            let info = mock_info("addr0000", &coins(500, "uusd"));
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Wrap {}).unwrap();
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: "addr0000".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let info = mock_info("addr0000", &coins(500, "uusd"));
+            let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Wrap {}).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+            let msg = ExecuteMsg::Unwrap {
+                amount: Uint128::new(100),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+        }
+    }
+
+    mod attestations {
+        use super::*;
+        use crate::msg::{AttestationsResponse, LatestAttestationResponse, Role};
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let msg = ExecuteMsg::SetRole {
+                role: Role::Attestor,
+                address: "auditor".into(),
+                granted: true,
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        fn submit(
+            deps: DepsMut,
+            sender: &str,
+            reserves: u128,
+            as_of: Timestamp,
+        ) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::SubmitAttestation {
+                reserves: Uint128::new(reserves),
+                as_of,
+                report_uri: "https://example.com/report.pdf".into(),
+                hash: "9f86d081884c7d65".into(),
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        }
+
+        fn mint(deps: DepsMut, amount: u128) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(amount),
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg)
+        }
+
+        fn latest(deps: Deps) -> LatestAttestationResponse {
+            from_binary(&query(deps, mock_env(), QueryMsg::LatestAttestation {}).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn keeps_history() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let now = mock_env().block.time;
+
+            let err = submit(deps.as_mut(), "creator", 100, now).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+            let err = submit(deps.as_mut(), "auditor", 100, now.plus_seconds(1)).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidAttestation { .. }));
+
+            submit(deps.as_mut(), "auditor", 100, now.minus_seconds(20)).unwrap();
+            submit(deps.as_mut(), "auditor", 300, now.minus_seconds(10)).unwrap();
+            let err = submit(deps.as_mut(), "auditor", 200, now.minus_seconds(10)).unwrap_err();
+            assert!(matches!(err, ContractError::InvalidAttestation { .. }));
+
+            let res = latest(deps.as_ref());
+            let attestation = res.attestation.unwrap();
+            assert_eq!(attestation.id, 1);
+            assert_eq!(attestation.reserves, Uint128::new(300));
+            assert_eq!(attestation.submitted_by, "auditor");
+            assert!(!res.enforced);
+
+            let msg = QueryMsg::Attestations {
+                start_after: Some(0),
+                limit: None,
+            };
+            let res: AttestationsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.attestations.len(), 1);
+            assert_eq!(res.attestations[0].id, 1);
+        }
+
+        #[test]
+        fn mint_is_held_to_reserves() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            mint(deps.as_mut(), 50).unwrap();
+
+            let msg = ExecuteMsg::UpdateReserveEnforcement { enabled: true };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            // Without an attestation nothing can be minted.
+            assert_eq!(
+                mint(deps.as_mut(), 1).unwrap_err(),
+                ContractError::ReservesExceeded {}
+            );
+
+            let as_of = mock_env().block.time.minus_seconds(60);
+            submit(deps.as_mut(), "auditor", 100, as_of).unwrap();
+            assert!(latest(deps.as_ref()).enforced);
+            mint(deps.as_mut(), 50).unwrap();
+            let err = mint(deps.as_mut(), 1).unwrap_err();
+            assert_eq!(err, ContractError::ReservesExceeded {});
+            assert_eq!(err.restriction().map(|r| r.code()), Some(15));
+
+            let msg = ExecuteMsg::UpdateReserveEnforcement { enabled: false };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            mint(deps.as_mut(), 1).unwrap();
+        }
+    }
+
+    mod requests {
+        use super::*;
+        use crate::msg::{RequestInfo, RequestKind, RequestStatus, RequestsResponse, Role};
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let minter = mock_info("creator", &[]);
+            let msg = ExecuteMsg::SetRole {
+                role: Role::Operator,
+                address: "operator".into(),
+                granted: true,
+            };
+            execute(deps.branch(), mock_env(), minter.clone(), msg).unwrap();
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+            };
+            execute(deps, mock_env(), minter, msg).unwrap();
+        }
+
+        fn open(
+            deps: DepsMut,
+            sender: &str,
+            kind: RequestKind,
+            amount: u128,
+        ) -> Result<Response, ContractError> {
+            let amount = Uint128::new(amount);
+            let reference = "wire 2291".to_string();
+            let msg = match kind {
+                RequestKind::Mint => ExecuteMsg::RequestMint { amount, reference },
+                RequestKind::Redeem => ExecuteMsg::RequestRedeem { amount, reference },
+            };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        }
+
+        fn fulfil(deps: DepsMut, sender: &str, id: u64) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::FulfilRequest { id };
+            execute(deps, mock_env(), mock_info(sender, &[]), msg)
+        }
+
+        fn request(deps: Deps, id: u64) -> RequestInfo {
+            from_binary(&query(deps, mock_env(), QueryMsg::Request { id }).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn mint_request() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            open(deps.as_mut(), "addr0001", RequestKind::Mint, 500).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::zero());
+
+            let err = fulfil(deps.as_mut(), "addr0001", 0).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+            fulfil(deps.as_mut(), "operator", 0).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(500));
+            let info = request(deps.as_ref(), 0);
+            assert_eq!(info.status, RequestStatus::Fulfilled);
+            assert_eq!(info.resolved_by, Some("operator".into()));
+
+            let err = fulfil(deps.as_mut(), "operator", 0).unwrap_err();
+            assert_eq!(err, ContractError::RequestClosed { id: 0 });
+            let err = fulfil(deps.as_mut(), "operator", 7).unwrap_err();
+            assert_eq!(err, ContractError::RequestNotFound { id: 7 });
+        }
+
+        #[test]
+        fn redeem_request_escrows_tokens() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+
+            open(deps.as_mut(), "addr0000", RequestKind::Redeem, 300).unwrap();
+            open(deps.as_mut(), "addr0000", RequestKind::Redeem, 200).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(500));
+            let err = open(deps.as_mut(), "addr0000", RequestKind::Redeem, 501).unwrap_err();
+            assert_eq!(err, ContractError::InsufficientBalance {});
+            assert_eq!(
+                get_balance(deps.as_ref(), MOCK_CONTRACT_ADDR),
+                Uint128::new(500)
+            );
+
+            fulfil(deps.as_mut(), "operator", 0).unwrap();
+            assert_eq!(
+                get_balance(deps.as_ref(), MOCK_CONTRACT_ADDR),
+                Uint128::new(200)
+            );
+            assert_eq!(
+                query_token_info(deps.as_ref()).unwrap().total_supply,
+                Uint128::new(700)
+            );
+
+            let msg = ExecuteMsg::RejectRequest {
+                id: 1,
+                reason: "bank details missing".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(700));
+            assert_eq!(
+                get_balance(deps.as_ref(), MOCK_CONTRACT_ADDR),
+                Uint128::zero()
+            );
+            assert_eq!(
+                request(deps.as_ref(), 1).reason,
+                Some("bank details missing".into())
+            );
+
+            let msg = QueryMsg::RequestsByStatus {
+                status: RequestStatus::Fulfilled,
+                start_after: None,
+                limit: None,
+            };
+            let res: RequestsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.requests.len(), 1);
+            assert_eq!(res.requests[0].id, 0);
+
+            let msg = QueryMsg::RequestsByRequester {
+                requester: "addr0000".into(),
+                start_after: Some(0),
+                limit: None,
+            };
+            let res: RequestsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.requests.len(), 1);
+            assert_eq!(res.requests[0].kind, RequestKind::Redeem);
+        }
+
+        #[test]
+        fn blocked_requesters() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            open(deps.as_mut(), "addr0001", RequestKind::Mint, 500).unwrap();
+            open(deps.as_mut(), "addr0000", RequestKind::Redeem, 300).unwrap();
+
+            for address in ["addr0000", "addr0001"] {
+                let msg = ExecuteMsg::AddToBlockedList {
+                    address: address.into(),
+                };
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            }
+
+            let err = open(deps.as_mut(), "addr0000", RequestKind::Redeem, 1).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+            for id in [0, 1] {
+                let err = fulfil(deps.as_mut(), "operator", id).unwrap_err();
+                assert_eq!(err, ContractError::Blocked {});
+            }
+
+            // Rejecting returns the escrow, which stays frozen with the requester.
+            let msg = ExecuteMsg::RejectRequest {
+                id: 1,
+                reason: "blocked".into(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("operator", &[]), msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(1000));
+        }
+    }
+
+    mod vesting {
+        use super::*;
+        use crate::msg::{
+            AccountStatusResponse, CanTransferResponse, VestingSchedule, VestingScheduleResponse,
+        };
+
+        fn at(seconds: u64) -> Env {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            env
+        }
+
+        fn linear(cliff: Option<u64>) -> VestingSchedule {
+            VestingSchedule::Linear {
+                start: at(0).block.time,
+                end: at(1000).block.time,
+                cliff: cliff.map(|cliff| at(cliff).block.time),
+            }
+        }
+
+        fn mint_vested(
+            deps: DepsMut,
+            schedule: VestingSchedule,
+        ) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::MintVested {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+                schedule,
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg)
+        }
+
+        fn transfer(deps: DepsMut, env: Env, amount: u128) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::Transfer {
+                recipient: "addr0001".into(),
+                amount: Uint128::new(amount),
+            };
+            execute(deps, env, mock_info("addr0000", &[]), msg)
+        }
+
+        fn schedule(deps: Deps, env: Env) -> VestingScheduleResponse {
+            let msg = QueryMsg::VestingSchedule {
+                address: "addr0000".into(),
+            };
+            from_binary(&query(deps, env, msg).unwrap()).unwrap()
+        }
+
+        fn set_blocked(deps: DepsMut, env: Env, blocked: bool) {
+            let address = "addr0000".to_string();
+            let msg = if blocked {
+                ExecuteMsg::AddToBlockedList { address }
+            } else {
+                ExecuteMsg::RemoveFromBlockedList { address }
+            };
+            execute(deps, env, mock_info("creator", &[]), msg).unwrap();
+        }
+
+        #[test]
+        fn linear_schedule_with_cliff() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::MintVested {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+                schedule: linear(None),
+            };
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
+            let err = mint_vested(deps.as_mut(), linear(Some(1001))).unwrap_err();
+            assert_eq!(err, ContractError::InvalidVestingSchedule {});
+
+            mint_vested(deps.as_mut(), linear(Some(250))).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(1000));
+            let err = mint_vested(deps.as_mut(), linear(None)).unwrap_err();
+            assert_eq!(err, ContractError::VestingExists {});
+
+            let err = transfer(deps.as_mut(), at(249), 1).unwrap_err();
+            assert_eq!(err, ContractError::TokensLocked {});
+            assert_eq!(err.restriction().map(|r| r.code()), Some(16));
+            let msg = QueryMsg::CanTransfer {
+                from: "addr0000".into(),
+                to: "addr0001".into(),
+                amount: Uint128::new(1),
+                spender: None,
+            };
+            let res: CanTransferResponse =
+                from_binary(&query(deps.as_ref(), at(249), msg).unwrap()).unwrap();
+            assert_eq!(res.code, 16);
+
+            let res = schedule(deps.as_ref(), at(250));
+            assert_eq!(res.vested, Uint128::new(250));
+            assert_eq!(res.locked, Uint128::new(750));
+            transfer(deps.as_mut(), at(250), 250).unwrap();
+            let err = transfer(deps.as_mut(), at(250), 1).unwrap_err();
+            assert_eq!(err, ContractError::TokensLocked {});
+            let msg = QueryMsg::AccountStatus {
+                address: "addr0000".into(),
+            };
+            let res: AccountStatusResponse =
+                from_binary(&query(deps.as_ref(), at(500), msg).unwrap()).unwrap();
+            assert_eq!(res.balance, Uint128::new(750));
+            assert_eq!(res.frozen, Uint128::new(500));
+
+            // Fully vested, the whole balance moves and a new schedule can start.
+            transfer(deps.as_mut(), at(1000), 750).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(1000));
+            let msg = ExecuteMsg::MintVested {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(1000),
+                schedule: linear(None),
+            };
+            execute(deps.as_mut(), at(1000), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        #[test]
+        fn cliff_leaves_other_tokens_free() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let msg = ExecuteMsg::Mint {
+                recipient: "addr0000".into(),
+                amount: Uint128::new(500),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let release_at = at(100).block.time;
+            mint_vested(deps.as_mut(), VestingSchedule::Cliff { release_at }).unwrap();
+
+            transfer(deps.as_mut(), at(99), 500).unwrap();
+            let err = transfer(deps.as_mut(), at(99), 1).unwrap_err();
+            assert_eq!(err, ContractError::TokensLocked {});
+            // Locked tokens can't be escrowed for redemption either.
+            let msg = ExecuteMsg::RequestRedeem {
+                amount: Uint128::new(1),
+                reference: "wire 17".into(),
+            };
+            let err = execute(deps.as_mut(), at(99), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::TokensLocked {});
+
+            transfer(deps.as_mut(), at(100), 1000).unwrap();
+        }
+
+        #[test]
+        fn blocking_pauses_vesting() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            mint_vested(deps.as_mut(), linear(None)).unwrap();
+
+            set_blocked(deps.as_mut(), at(100), true);
+            let res = schedule(deps.as_ref(), at(600));
+            assert_eq!(res.vested, Uint128::new(100));
+            assert!(res.paused);
+            assert_eq!(res.paused_for, 500);
+
+            set_blocked(deps.as_mut(), at(600), false);
+            let res = schedule(deps.as_ref(), at(700));
+            assert_eq!(res.vested, Uint128::new(200));
+            assert!(!res.paused);
+            assert_eq!(res.paused_for, 500);
+            let res = schedule(deps.as_ref(), at(1499));
+            assert_eq!(res.locked, Uint128::new(1));
+        }
+
+        #[test]
+        fn seizing_unvested_tokens() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            mint_vested(deps.as_mut(), linear(None)).unwrap();
+
+            let msg = ExecuteMsg::DestroyUnvestedFunds {
+                address: "addr0000".into(),
+            };
+            let err = execute(
+                deps.as_mut(),
+                at(300),
+                mock_info("creator", &[]),
+                msg.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::NotBlocked {});
+
+            set_blocked(deps.as_mut(), at(300), true);
+            execute(
+                deps.as_mut(),
+                at(400),
+                mock_info("creator", &[]),
+                msg.clone(),
+            )
+            .unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(300));
+            assert_eq!(
+                query_token_info(deps.as_ref()).unwrap().total_supply,
+                Uint128::new(300)
+            );
+            assert_eq!(schedule(deps.as_ref(), at(400)).schedule, None);
+
+            let err = execute(deps.as_mut(), at(400), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::NotVesting {});
+        }
     }
 
-    mod instantiate {
+    mod holds {
         use super::*;
+        use crate::msg::{HoldInfo, HoldStatus, HoldsResponse, Role};
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+        use cw20::Expiration;
 
-        #[test]
-        fn basic() {
-            let mut deps = mock_dependencies();
-            let amount = Uint128::new(11223344);
-            do_instantiate(deps.as_mut());
-
+        fn setup(mut deps: DepsMut) {
+            do_instantiate(deps.branch());
+            let minter = mock_info("creator", &[]);
+            let msg = ExecuteMsg::SetRole {
+                role: Role::Compliance,
+                address: "compliance".into(),
+                granted: true,
+            };
+            execute(deps.branch(), mock_env(), minter.clone(), msg).unwrap();
             let msg = ExecuteMsg::Mint {
                 recipient: "addr0000".into(),
-                amount: amount,
+                amount: Uint128::new(1000),
+            };
+            execute(deps, mock_env(), minter, msg).unwrap();
+        }
+
+        fn expires_in(seconds: u64) -> Expiration {
+            Expiration::AtTime(mock_env().block.time.plus_seconds(seconds))
+        }
+
+        fn hold(deps: DepsMut, to: &str, amount: u128) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::Hold {
+                to: to.into(),
+                amount: Uint128::new(amount),
+                expires: expires_in(100),
             };
+            execute(deps, mock_env(), mock_info("addr0000", &[]), msg)
+        }
 
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+        fn settle(
+            deps: DepsMut,
+            env: Env,
+            sender: &str,
+            id: u64,
+            release: bool,
+        ) -> Result<Response, ContractError> {
+            let msg = if release {
+                ExecuteMsg::Release { id }
+            } else {
+                ExecuteMsg::Revert { id }
+            };
+            execute(deps, env, mock_info(sender, &[]), msg)
+        }
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+        fn block(deps: DepsMut, address: &str) {
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: address.into(),
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        #[test]
+        fn release_and_revert() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
 
+            hold(deps.as_mut(), "addr0001", 300).unwrap();
+            hold(deps.as_mut(), "addr0001", 200).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(500));
             assert_eq!(
-                query_token_info(deps.as_ref()).unwrap(),
-                TokenInfoResponse {
-                    name: "Auto Gen".to_string(),
-                    symbol: "AUTO".to_string(),
-                    decimals: 6,
-                    total_supply: amount,
-                }
+                get_balance(deps.as_ref(), MOCK_CONTRACT_ADDR),
+                Uint128::new(500)
             );
+
+            for sender in ["addr0000", "addr0001"] {
+                let err = settle(deps.as_mut(), mock_env(), sender, 0, true).unwrap_err();
+                assert_eq!(err, ContractError::Unauthorized {});
+                let err = settle(deps.as_mut(), mock_env(), sender, 0, false).unwrap_err();
+                assert_eq!(err, ContractError::Unauthorized {});
+            }
+
+            settle(deps.as_mut(), mock_env(), "compliance", 0, true).unwrap();
+            settle(deps.as_mut(), mock_env(), "compliance", 1, false).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(700));
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(300));
             assert_eq!(
-                get_balance(deps.as_ref(), "addr0000"),
-                Uint128::new(11223344)
+                get_balance(deps.as_ref(), MOCK_CONTRACT_ADDR),
+                Uint128::zero()
             );
+
+            let err = settle(deps.as_mut(), mock_env(), "compliance", 1, true).unwrap_err();
+            assert_eq!(err, ContractError::HoldClosed { id: 1 });
+            let err = settle(deps.as_mut(), mock_env(), "compliance", 2, true).unwrap_err();
+            assert_eq!(err, ContractError::HoldNotFound { id: 2 });
+
+            let info: HoldInfo =
+                from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Hold { id: 0 }).unwrap())
+                    .unwrap();
+            assert_eq!(info.status, HoldStatus::Released);
+            assert_eq!(info.resolved_by, Some("compliance".into()));
+
+            let msg = QueryMsg::HoldsByRecipient {
+                recipient: "addr0001".into(),
+                start_after: Some(0),
+                limit: None,
+            };
+            let res: HoldsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.holds.len(), 1);
+            assert_eq!(res.holds[0].status, HoldStatus::Reverted);
+            let msg = QueryMsg::HoldsBySender {
+                sender: "addr0001".into(),
+                start_after: None,
+                limit: None,
+            };
+            let res: HoldsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert!(res.holds.is_empty());
         }
 
         #[test]
-        fn blocked() {
+        fn anyone_releases_after_expiry() {
             let mut deps = mock_dependencies();
-            let amount = Uint128::from(11223344u128);
-            do_instantiate(deps.as_mut());
-
-            // Mint to addr0000 from creator.
-            let msg = ExecuteMsg::Mint {
-                recipient: "addr0000".into(),
-                amount: amount,
+            setup(deps.as_mut());
+            let msg = ExecuteMsg::Hold {
+                to: "addr0001".into(),
+                amount: Uint128::new(300),
+                expires: Expiration::AtTime(mock_env().block.time),
             };
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidExpiration {});
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            hold(deps.as_mut(), "addr0001", 300).unwrap();
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(99);
+            let err = settle(deps.as_mut(), env.clone(), "addr0001", 0, true).unwrap_err();
+            assert_eq!(err, ContractError::Unauthorized {});
 
-            // Block addr0000 from creator.
-            let msg = ExecuteMsg::AddToBlockedList {
-                address: "addr0000".into(),
-            };
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+            env.block.time = env.block.time.plus_seconds(1);
+            settle(deps.as_mut(), env, "addr0001", 0, true).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(300));
+        }
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+        #[test]
+        fn blocked_parties() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            block(deps.as_mut(), "addr0002");
+            let err = hold(deps.as_mut(), "addr0002", 100).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
 
-            // Attempt a transfer from addr0000 to addr0001.
-            let msg = ExecuteMsg::Transfer {
-                recipient: "addr0001".into(),
-                amount: Uint128::from(1000000u128),
-            };
-            let info = mock_info("addr0000", &[]);
-            let env = mock_env();
+            hold(deps.as_mut(), "addr0001", 100).unwrap();
+            hold(deps.as_mut(), "addr0003", 100).unwrap();
+            block(deps.as_mut(), "addr0001");
+            let err = settle(deps.as_mut(), mock_env(), "compliance", 0, true).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
 
-            // Ensure transfer was blocked.
-            let err = execute(deps.as_mut(), env, info, msg.clone()).unwrap_err();
+            block(deps.as_mut(), "addr0000");
+            let err = settle(deps.as_mut(), mock_env(), "compliance", 1, true).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+            let err = hold(deps.as_mut(), "addr0003", 100).unwrap_err();
             assert_eq!(err, ContractError::Blocked {});
 
-            // Unblock addr0000.
-            let unblock_msg = ExecuteMsg::RemoveFromBlockedList {
-                address: "addr0000".into(),
+            // Reverting returns the tokens, which stay frozen with the blocked sender.
+            settle(deps.as_mut(), mock_env(), "compliance", 0, false).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::new(900));
+        }
+
+        #[test]
+        fn release_pays_fee() {
+            let mut deps = mock_dependencies();
+            setup(deps.as_mut());
+            let minter = mock_info("creator", &[]);
+            let msg = ExecuteMsg::UpdateFeeConfig {
+                collector: "collector".into(),
+                basis_points: 100,
+                min_fee: Uint128::new(10),
+                max_fee: None,
             };
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+            execute(deps.as_mut(), mock_env(), minter.clone(), msg).unwrap();
 
-            let res = execute(deps.as_mut(), env, info, unblock_msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            hold(deps.as_mut(), "addr0001", 10).unwrap();
+            hold(deps.as_mut(), "addr0001", 500).unwrap();
+            // The whole of the first hold goes to the fee.
+            settle(deps.as_mut(), mock_env(), "compliance", 0, true).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::zero());
+            assert_eq!(get_balance(deps.as_ref(), "collector"), Uint128::new(10));
 
-            // Attempt a transfer from addr0000 to addr0001.
-            let info = mock_info("addr0000", &[]);
-            let env = mock_env();
+            // The collector's maximum balance applies like on a transfer.
+            let msg = ExecuteMsg::UpdateMaxBalance {
+                max_balance: Some(Uint128::new(500)),
+            };
+            execute(deps.as_mut(), mock_env(), minter.clone(), msg).unwrap();
+            let msg = ExecuteMsg::Mint {
+                recipient: "collector".into(),
+                amount: Uint128::new(490),
+            };
+            execute(deps.as_mut(), mock_env(), minter, msg).unwrap();
+            let err = settle(deps.as_mut(), mock_env(), "compliance", 1, true).unwrap_err();
+            assert_eq!(err, ContractError::MaxBalanceExceeded {});
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::zero());
+        }
+    }
 
-            // Ensure transfer was successful.
-            let res = execute(deps.as_mut(), env, info, msg.clone()).unwrap();
-            assert_eq!(0, res.messages.len());
-            assert_eq!(
-                get_balance(deps.as_ref(), "addr0001"),
-                Uint128::new(1000000)
-            );
+    mod permit {
+        use super::*;
+        use crate::msg::{NonceResponse, PermitPayload};
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+        use cw20::{AllowanceResponse, Expiration};
+
+        fn payload(owner: &TestKey, amount: u128, nonce: u64) -> PermitPayload {
+            PermitPayload {
+                chain_id: mock_env().block.chain_id,
+                contract: MOCK_CONTRACT_ADDR.into(),
+                owner: owner.address(),
+                spender: "addr0001".into(),
+                amount: Uint128::new(amount),
+                expires: Expiration::AtHeight(mock_env().block.height + 100),
+                nonce,
+            }
+        }
+
+        fn permit_msg(payload: &PermitPayload, signature: Binary, pubkey: Binary) -> ExecuteMsg {
+            ExecuteMsg::Permit {
+                owner: payload.owner.clone(),
+                spender: payload.spender.clone(),
+                amount: payload.amount,
+                expires: payload.expires,
+                nonce: payload.nonce,
+                signature,
+                pubkey,
+            }
+        }
+
+        fn submit(
+            deps: DepsMut,
+            payload: &PermitPayload,
+            key: &TestKey,
+        ) -> Result<Response, ContractError> {
+            let msg = permit_msg(payload, key.sign(payload), key.pubkey());
+            execute(deps, mock_env(), mock_info("relayer", &[]), msg)
+        }
+
+        fn allowance(deps: Deps, owner: &TestKey) -> AllowanceResponse {
+            query_allowance(deps, owner.address(), "addr0001".into()).unwrap()
         }
 
         #[test]
-        fn ensure_uppercase_address_can_not_evade() {
+        fn signed_allowance() {
             let mut deps = mock_dependencies();
-            let amount = Uint128::new(11223344);
             do_instantiate(deps.as_mut());
-
-            // Mint to uppercased address ADDR0000 from creator.
+            let owner = TestKey::new(1);
             let msg = ExecuteMsg::Mint {
-                recipient: "ADDR0000".into(),
-                amount: amount,
+                recipient: owner.address(),
+                amount: Uint128::new(1000),
             };
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
-
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
 
-            // Block lowercase addr0000 from creator.
-            let msg = ExecuteMsg::AddToBlockedList {
-                address: "addr0000".into(),
+            let first = payload(&owner, 500, 0);
+            submit(deps.as_mut(), &first, &owner).unwrap();
+            assert_eq!(
+                allowance(deps.as_ref(), &owner).allowance,
+                Uint128::new(500)
+            );
+            let msg = QueryMsg::Nonce {
+                address: owner.address(),
             };
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+            let res: NonceResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.nonce, 1);
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            let err = submit(deps.as_mut(), &first, &owner).unwrap_err();
+            assert_eq!(err, ContractError::InvalidNonce { expected: 1 });
 
-            // Attempt a transfer from uppercased address ADDR0000 to addr0001.
-            let msg = ExecuteMsg::Transfer {
-                recipient: "addr0001".into(),
-                amount: Uint128::from(1000000u128),
+            let msg = ExecuteMsg::TransferFrom {
+                owner: owner.address(),
+                recipient: "addr0002".into(),
+                amount: Uint128::new(200),
             };
-            let info = mock_info("ADDR0000", &[]);
-            let env = mock_env();
+            execute(deps.as_mut(), mock_env(), mock_info("addr0001", &[]), msg).unwrap();
+            assert_eq!(get_balance(deps.as_ref(), "addr0002"), Uint128::new(200));
 
-            // Ensure transfer was blocked.
-            let err = execute(deps.as_mut(), env, info, msg.clone()).unwrap_err();
-            assert_eq!(err, ContractError::Blocked {});
+            // A later permit replaces the allowance, and a zero amount revokes it.
+            submit(deps.as_mut(), &payload(&owner, 50, 1), &owner).unwrap();
+            assert_eq!(allowance(deps.as_ref(), &owner).allowance, Uint128::new(50));
+            submit(deps.as_mut(), &payload(&owner, 0, 2), &owner).unwrap();
+            assert_eq!(
+                allowance(deps.as_ref(), &owner),
+                AllowanceResponse::default()
+            );
         }
 
         #[test]
-        fn destroy_blocked_funds() {
+        fn bad_signatures() {
             let mut deps = mock_dependencies();
-            let amount = Uint128::from(11223344u128);
             do_instantiate(deps.as_mut());
+            let owner = TestKey::new(1);
+            let other = TestKey::new(2);
+            let signed = payload(&owner, 500, 0);
 
-            // Mint to addr0000 from creator.
-            let msg = ExecuteMsg::Mint {
-                recipient: "addr0000".into(),
-                amount: amount,
-            };
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+            let err = submit(deps.as_mut(), &signed, &other).unwrap_err();
+            assert_eq!(err, ContractError::PubkeyMismatch {});
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            let mut tampered = signed.clone();
+            tampered.amount = Uint128::new(5000);
+            let msg = permit_msg(&tampered, owner.sign(&signed), owner.pubkey());
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidSignature {});
 
-            // Attempt destroy
-            let msg = ExecuteMsg::DestroyBlockedFunds {
-                address: "addr0000".into(),
-            };
+            let mut other_chain = signed.clone();
+            other_chain.chain_id = "other-chain".into();
+            let msg = permit_msg(&signed, owner.sign(&other_chain), owner.pubkey());
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidSignature {});
 
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+            let err = submit(deps.as_mut(), &payload(&owner, 500, 1), &owner).unwrap_err();
+            assert_eq!(err, ContractError::InvalidNonce { expected: 0 });
 
-            // ensure you can't burn destroy that aren't blocked.
-            let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
-            assert_eq!(err, ContractError::NotBlocked {});
+            let mut expired = signed.clone();
+            expired.expires = Expiration::AtHeight(mock_env().block.height);
+            let err = submit(deps.as_mut(), &expired, &owner).unwrap_err();
+            assert_eq!(err, ContractError::Expired {});
+        }
 
-            // Block addr0000 from creator.
-            let msg = ExecuteMsg::AddToBlockedList {
-                address: "addr0000".into(),
+        #[test]
+        fn blocked_parties() {
+            let mut deps = mock_dependencies();
+            do_instantiate(deps.as_mut());
+            let owner = TestKey::new(1);
+            let signed = payload(&owner, 500, 0);
+
+            for address in ["addr0001".to_string(), owner.address()] {
+                let msg = ExecuteMsg::AddToBlockedList {
+                    address: address.clone(),
+                };
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+                let err = submit(deps.as_mut(), &signed, &owner).unwrap_err();
+                assert_eq!(err, ContractError::Blocked {});
+                let msg = ExecuteMsg::RemoveFromBlockedList { address };
+                execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            }
+            submit(deps.as_mut(), &signed, &owner).unwrap();
+        }
+    }
+
+    mod execute_signed {
+        use super::*;
+        use crate::msg::{NonceResponse, SignedDocument, SignedMsg, SignedPayload};
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+        use cw20::{Cw20ReceiveMsg, Expiration};
+
+        fn setup(mut deps: DepsMut, signer: &TestKey) {
+            do_instantiate(deps.branch());
+            let msg = ExecuteMsg::Mint {
+                recipient: signer.address(),
+                amount: Uint128::new(1000),
             };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
 
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+        fn transfer(signer: &TestKey, amount: u128, relayer_fee: Option<u128>) -> SignedPayload {
+            SignedPayload {
+                signer: signer.address(),
+                msg: SignedMsg::Transfer {
+                    recipient: "addr0001".into(),
+                    amount: Uint128::new(amount),
+                },
+                expires: Expiration::AtHeight(mock_env().block.height + 10),
+                relayer_fee: relayer_fee.map(Uint128::new),
+            }
+        }
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+        fn document(payload: &SignedPayload, nonce: u64) -> SignedDocument {
+            SignedDocument {
+                chain_id: mock_env().block.chain_id,
+                contract: MOCK_CONTRACT_ADDR.into(),
+                payload: payload.clone(),
+                nonce,
+            }
+        }
 
-            // Destroy blocked funds.
-            let msg = ExecuteMsg::DestroyBlockedFunds {
-                address: "addr0000".into(),
+        fn submit(
+            deps: DepsMut,
+            key: &TestKey,
+            payload: SignedPayload,
+            nonce: u64,
+        ) -> Result<Response, ContractError> {
+            let signature = key.sign(&document(&payload, nonce));
+            let msg = ExecuteMsg::ExecuteSigned {
+                payload,
+                signature,
+                pubkey: key.pubkey(),
+                nonce,
             };
+            execute(deps, mock_env(), mock_info("relayer", &[]), msg)
+        }
 
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+        #[test]
+        fn relayed_transfer() {
+            let mut deps = mock_dependencies();
+            let signer = TestKey::new(1);
+            setup(deps.as_mut(), &signer);
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            let res = submit(deps.as_mut(), &signer, transfer(&signer, 300, Some(5)), 0).unwrap();
+            assert!(res.attributes.contains(&attr("restriction_code", "0")));
+            assert!(res.attributes.contains(&attr("relayer_fee", "5")));
+            assert_eq!(
+                get_balance(deps.as_ref(), signer.address()),
+                Uint128::new(695)
+            );
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::new(300));
+            assert_eq!(get_balance(deps.as_ref(), "relayer"), Uint128::new(5));
 
-            assert_eq!(get_balance(deps.as_ref(), "addr0000"), Uint128::zero());
+            let err =
+                submit(deps.as_mut(), &signer, transfer(&signer, 300, Some(5)), 0).unwrap_err();
+            assert_eq!(err, ContractError::InvalidNonce { expected: 1 });
+            // Permits and signed transfers share the signer's nonces.
+            let msg = QueryMsg::Nonce {
+                address: signer.address(),
+            };
+            let res: NonceResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(res.nonce, 1);
+
+            // The fee comes out of the signer's balance like any transfer.
+            let err =
+                submit(deps.as_mut(), &signer, transfer(&signer, 695, Some(1)), 1).unwrap_err();
+            assert_eq!(err, ContractError::InsufficientBalance {});
         }
 
         #[test]
-        fn queries_work() {
+        fn relayed_send() {
             let mut deps = mock_dependencies();
-            let env = mock_env();
-            do_instantiate(deps.as_mut());
-            let data = query(
-                deps.as_ref(),
-                env,
-                QueryMsg::IsBlocked {
-                    address: String::from("addr0000"),
+            let signer = TestKey::new(1);
+            setup(deps.as_mut(), &signer);
+
+            let payload = SignedPayload {
+                signer: signer.address(),
+                msg: SignedMsg::Send {
+                    contract: "contract0000".into(),
+                    amount: Uint128::new(100),
+                    msg: Binary::from(b"{}"),
                 },
-            )
-            .unwrap();
-            let loaded: BlockedResponse = from_binary(&data).unwrap();
-            assert_eq!(loaded.blocked, false);
+                expires: Expiration::Never {},
+                relayer_fee: None,
+            };
+            let res = submit(deps.as_mut(), &signer, payload, 0).unwrap();
+            let receive = Cw20ReceiveMsg {
+                sender: signer.address(),
+                amount: Uint128::new(100),
+                msg: Binary::from(b"{}"),
+            };
+            assert_eq!(
+                res.messages[0].msg,
+                receive.into_cosmos_msg("contract0000").unwrap()
+            );
+        }
 
-            // Block addr0000 from creator.
-            let msg = ExecuteMsg::AddToBlockedList {
-                address: "addr0000".into(),
+        #[test]
+        fn rejected_messages() {
+            let mut deps = mock_dependencies();
+            let signer = TestKey::new(1);
+            let other = TestKey::new(2);
+            setup(deps.as_mut(), &signer);
+
+            let err = submit(deps.as_mut(), &other, transfer(&signer, 300, None), 0).unwrap_err();
+            assert_eq!(err, ContractError::PubkeyMismatch {});
+
+            let msg = ExecuteMsg::ExecuteSigned {
+                payload: transfer(&signer, 900, None),
+                signature: signer.sign(&document(&transfer(&signer, 300, None), 0)),
+                pubkey: signer.pubkey(),
+                nonce: 0,
             };
-            let info = mock_info("creator", &[]);
-            let env = mock_env();
+            let err =
+                execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg).unwrap_err();
+            assert_eq!(err, ContractError::InvalidSignature {});
 
-            let res = execute(deps.as_mut(), env, info, msg).unwrap();
-            assert_eq!(0, res.messages.len());
+            let mut expired = transfer(&signer, 300, None);
+            expired.expires = Expiration::AtHeight(mock_env().block.height);
+            let err = submit(deps.as_mut(), &signer, expired, 0).unwrap_err();
+            assert_eq!(err, ContractError::SignatureExpired {});
 
-            let env = mock_env();
-            let data = query(
-                deps.as_ref(),
-                env,
-                QueryMsg::IsBlocked {
-                    address: String::from("addr0000"),
-                },
-            )
-            .unwrap();
-            let loaded: BlockedResponse = from_binary(&data).unwrap();
-            assert_eq!(loaded.blocked, true);
+            let msg = ExecuteMsg::AddToBlockedList {
+                address: signer.address(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+            let err = submit(deps.as_mut(), &signer, transfer(&signer, 300, None), 0).unwrap_err();
+            assert_eq!(err, ContractError::Blocked {});
+            assert_eq!(get_balance(deps.as_ref(), "addr0001"), Uint128::zero());
         }
     }
 }
@@ -1,37 +1,198 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp};
+use cw0::PaymentError;
 use thiserror::Error;
 
+use crate::msg::Restriction;
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
-    #[error("Unauthorized")]
+    #[error("Unauthorized (restriction 9)")]
     Unauthorized {},
 
     #[error("Cannot set to own account")]
     CannotSetOwnAccount {},
 
-    #[error("Address is on the blocklist")]
+    #[error("Address is on the blocklist (restriction 1)")]
     Blocked {},
 
-    #[error("Address is not on the blocklist")]
+    #[error("Address is not on the blocklist (restriction 10)")]
     NotBlocked {},
 
-    #[error("Invalid zero amount")]
+    #[error("Invalid address: {address}")]
+    InvalidAddress { address: String },
+
+    #[error("Address {address} does not use the {prefix} prefix")]
+    InvalidAddressPrefix { address: String, prefix: String },
+
+    #[error("Invalid zero amount (restriction 2)")]
     InvalidZeroAmount {},
 
-    #[error("Allowance is expired")]
+    #[error("Balance is too low (restriction 3)")]
+    InsufficientBalance {},
+
+    #[error("Allowance is expired (restriction 5)")]
     Expired {},
 
-    #[error("No allowance for this account")]
+    #[error("No allowance for this account (restriction 4)")]
     NoAllowance {},
 
-    #[error("Minting cannot exceed the cap")]
+    #[error("Allowance is too low (restriction 6)")]
+    InsufficientAllowance {},
+
+    #[error("Minting cannot exceed the cap (restriction 11)")]
     CannotExceedCap {},
 
     #[error("Duplicate initial balance addresses")]
     DuplicateInitialBalanceAddresses {},
+
+    #[error("Recipient balance would exceed the maximum balance (restriction 7)")]
+    MaxBalanceExceeded {},
+
+    #[error("Invalid fee config: {reason}")]
+    InvalidFeeConfig { reason: String },
+
+    #[error("Fee collector cannot be on the blocklist (restriction 12)")]
+    BlockedFeeCollector {},
+
+    #[error("Receiving contract is not on the send whitelist (restriction 8)")]
+    ContractNotWhitelisted {},
+
+    #[error("This action must be scheduled through the timelock (restriction 13)")]
+    TimelockRequired {},
+
+    #[error("Action cannot be scheduled before {earliest}")]
+    EtaTooEarly { earliest: Timestamp },
+
+    #[error("Action {id} cannot run before {eta}")]
+    ActionNotReady { id: u64, eta: Timestamp },
+
+    #[error("No scheduled action with id {id}")]
+    ActionNotFound { id: u64 },
+
+    #[error("This action must be proposed and approved (restriction 14)")]
+    ApprovalRequired {},
+
+    #[error("Invalid approval config: {reason}")]
+    InvalidApprovalConfig { reason: String },
+
+    #[error("No proposal with id {id}")]
+    ProposalNotFound { id: u64 },
+
+    #[error("Proposal {id} is no longer open")]
+    ProposalClosed { id: u64 },
+
+    #[error("Already voted on this proposal")]
+    AlreadyVoted {},
+
+    #[error("Invalid evidence URI")]
+    InvalidEvidenceUri {},
+
+    #[error("A review is already pending for this address")]
+    ReviewPending {},
+
+    #[error("No pending review for this address")]
+    NoPendingReview {},
+
+    #[error("Invalid legal reference")]
+    InvalidLegalReference {},
+
+    #[error("Account was already recovered to {replacement}")]
+    AlreadyRecovered { replacement: String },
+
+    #[error("Channel must be unordered and use version cw20-blocklist-1")]
+    InvalidIbcChannel {},
+
+    #[error("Counterpart is not a trusted IBC peer")]
+    UntrustedIbcPeer {},
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Wrapping is disabled")]
+    WrapDisabled {},
+
+    #[error("Cannot change the wrapped denom while wrapped tokens are outstanding")]
+    ReservesOutstanding {},
+
+    #[error("Not enough reserves to unwrap")]
+    InsufficientReserves {},
+
+    #[error("Minting cannot exceed the attested reserves (restriction 15)")]
+    ReservesExceeded {},
+
+    #[error("Invalid attestation: {reason}")]
+    InvalidAttestation { reason: String },
+
+    #[error("Invalid reference")]
+    InvalidReference {},
+
+    #[error("Request {id} not found")]
+    RequestNotFound { id: u64 },
+
+    #[error("Request {id} is no longer pending")]
+    RequestClosed { id: u64 },
+
+    #[error("Tokens are still vesting (restriction 16)")]
+    TokensLocked {},
+
+    #[error("Invalid vesting schedule")]
+    InvalidVestingSchedule {},
+
+    #[error("Address already has tokens vesting")]
+    VestingExists {},
+
+    #[error("Address has no tokens vesting")]
+    NotVesting {},
+
+    #[error("Expiration is already in the past")]
+    InvalidExpiration {},
+
+    #[error("Hold {id} not found")]
+    HoldNotFound { id: u64 },
+
+    #[error("Hold {id} is no longer pending")]
+    HoldClosed { id: u64 },
+
+    #[error("Public key does not belong to the signer")]
+    PubkeyMismatch {},
+
+    #[error("Invalid signature")]
+    InvalidSignature {},
+
+    #[error("Invalid nonce, expected {expected}")]
+    InvalidNonce { expected: u64 },
+
+    #[error("Signed message has expired")]
+    SignatureExpired {},
+}
+
+impl ContractError {
+    /// Returns the restriction this error stands for, if it is a policy rejection. The
+    /// message of such an error ends with its restriction code.
+    pub fn restriction(&self) -> Option<Restriction> {
+        match self {
+            ContractError::Blocked {} => Some(Restriction::Blocked),
+            ContractError::InvalidZeroAmount {} => Some(Restriction::ZeroAmount),
+            ContractError::InsufficientBalance {} => Some(Restriction::InsufficientBalance),
+            ContractError::NoAllowance {} => Some(Restriction::NoAllowance),
+            ContractError::Expired {} => Some(Restriction::AllowanceExpired),
+            ContractError::InsufficientAllowance {} => Some(Restriction::InsufficientAllowance),
+            ContractError::MaxBalanceExceeded {} => Some(Restriction::MaxBalanceExceeded),
+            ContractError::ContractNotWhitelisted {} => Some(Restriction::ContractNotWhitelisted),
+            ContractError::Unauthorized {} => Some(Restriction::Unauthorized),
+            ContractError::NotBlocked {} => Some(Restriction::NotBlocked),
+            ContractError::CannotExceedCap {} => Some(Restriction::CapExceeded),
+            ContractError::BlockedFeeCollector {} => Some(Restriction::BlockedFeeCollector),
+            ContractError::TimelockRequired {} => Some(Restriction::TimelockRequired),
+            ContractError::ApprovalRequired {} => Some(Restriction::ApprovalRequired),
+            ContractError::ReservesExceeded {} => Some(Restriction::ReservesExceeded),
+            ContractError::TokensLocked {} => Some(Restriction::TokensLocked),
+            _ => None,
+        }
+    }
 }
 
 impl From<cw20_base::ContractError> for ContractError {
@@ -56,3 +217,54 @@ impl From<cw20_base::ContractError> for ContractError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_carry_restriction_codes() {
+        let errors = [
+            ContractError::Blocked {},
+            ContractError::InvalidZeroAmount {},
+            ContractError::InsufficientBalance {},
+            ContractError::NoAllowance {},
+            ContractError::Expired {},
+            ContractError::InsufficientAllowance {},
+            ContractError::MaxBalanceExceeded {},
+            ContractError::ContractNotWhitelisted {},
+            ContractError::Unauthorized {},
+            ContractError::NotBlocked {},
+            ContractError::CannotExceedCap {},
+            ContractError::BlockedFeeCollector {},
+            ContractError::TimelockRequired {},
+            ContractError::ApprovalRequired {},
+            ContractError::ReservesExceeded {},
+            ContractError::TokensLocked {},
+        ];
+        for err in errors.iter() {
+            let restriction = err.restriction().unwrap();
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "{} (restriction {})",
+                    restriction.message(),
+                    restriction.code()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn codes_are_unique() {
+        for restriction in Restriction::ALL.iter().copied() {
+            assert_ne!(restriction.code(), crate::msg::RESTRICTION_SUCCESS);
+            assert_eq!(
+                Restriction::from_code(restriction.code()),
+                Some(restriction)
+            );
+        }
+        assert_eq!(Restriction::from_code(0), None);
+        assert_eq!(Restriction::from_code(200), None);
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Binary, CanonicalAddr, Deps, DepsMut, Env,
+    IbcBasicResponse, IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcMsg, IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, Order, Response, StdResult,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::address::normalize;
+use crate::contract::{block_address, unblock_address};
+use crate::error::ContractError;
+use crate::msg::{ChannelInfo, IbcChannelsResponse, RemoteOrigin};
+use crate::state::{BLOCK_RECORDS, IBC_CHANNELS, IBC_PEERS};
+
+/// The channel version both ends must agree on.
+pub const IBC_VERSION: &str = "cw20-blocklist-1";
+pub const IBC_ORDERING: IbcOrder = IbcOrder::Unordered;
+
+/// How long, in seconds, a relayer has to deliver a `BlocklistUpdate`.
+pub const PACKET_LIFETIME: u64 = 24 * 60 * 60;
+
+/// The packet sent to counterpart contracts whenever an address is blocked or unblocked here.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlocklistUpdate {
+    /// The address as it is known on the sending chain.
+    pub address: String,
+    /// The canonical form of `address`, which the receiving chain renders with its own prefix.
+    pub canonical_address: Binary,
+    pub blocked: bool,
+}
+
+/// The acknowledgement written by the receiving contract, in the same shape as ICS-20's.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BlocklistAck {
+    Result(Binary),
+    Error(String),
+}
+
+fn ack_success() -> Binary {
+    to_binary(&BlocklistAck::Result(b"1".into())).unwrap()
+}
+
+fn ack_fail(err: String) -> Binary {
+    to_binary(&BlocklistAck::Error(err)).unwrap()
+}
+
+pub fn set_ibc_peer(
+    deps: DepsMut,
+    connection_id: String,
+    port_id: String,
+    trusted: bool,
+) -> StdResult<Response> {
+    if trusted {
+        IBC_PEERS.save(deps.storage, (&connection_id, &port_id), &true)?;
+    } else {
+        IBC_PEERS.remove(deps.storage, (&connection_id, &port_id));
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("method", "set_ibc_peer"),
+        attr("connection_id", connection_id),
+        attr("port_id", port_id),
+        attr("trusted", trusted.to_string()),
+    ]))
+}
+
+pub fn query_ibc_channels(deps: Deps) -> StdResult<IbcChannelsResponse> {
+    let channels = IBC_CHANNELS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, channel)| channel))
+        .collect::<StdResult<_>>()?;
+    Ok(IbcChannelsResponse { channels })
+}
+
+/// Builds a `BlocklistUpdate` for `address` to every open channel.
+pub(crate) fn blocklist_update_msgs(
+    deps: Deps,
+    env: &Env,
+    address: &Addr,
+    blocked: bool,
+) -> StdResult<Vec<IbcMsg>> {
+    let channels: Vec<String> = IBC_CHANNELS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    if channels.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let packet = BlocklistUpdate {
+        address: address.to_string(),
+        canonical_address: deps.api.addr_canonicalize(address.as_str())?.0,
+        blocked,
+    };
+    let data = to_binary(&packet)?;
+    Ok(channels
+        .into_iter()
+        .map(|channel_id| IbcMsg::SendPacket {
+            channel_id,
+            data: data.clone(),
+            timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+        })
+        .collect())
+}
+
+/// Only channels to a trusted counterpart, with the expected ordering and version, may open.
+fn check_channel(deps: Deps, channel: &IbcChannel) -> Result<(), ContractError> {
+    if channel.version != IBC_VERSION || channel.order != IBC_ORDERING {
+        return Err(ContractError::InvalidIbcChannel {});
+    }
+    let trusted = IBC_PEERS
+        .may_load(
+            deps.storage,
+            (
+                &channel.connection_id,
+                &channel.counterparty_endpoint.port_id,
+            ),
+        )?
+        .unwrap_or_default();
+    if !trusted {
+        return Err(ContractError::UntrustedIbcPeer {});
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<(), ContractError> {
+    if let Some(version) = msg.counterparty_version() {
+        if version != IBC_VERSION {
+            return Err(ContractError::InvalidIbcChannel {});
+        }
+    }
+    check_channel(deps.as_ref(), msg.channel())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    check_channel(deps.as_ref(), channel)?;
+
+    let info = ChannelInfo {
+        channel_id: channel.endpoint.channel_id.clone(),
+        connection_id: channel.connection_id.clone(),
+        counterparty_port_id: channel.counterparty_endpoint.port_id.clone(),
+        counterparty_channel_id: channel.counterparty_endpoint.channel_id.clone(),
+    };
+    IBC_CHANNELS.save(deps.storage, &info.channel_id, &info)?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "ibc_channel_connect")
+        .add_attribute("channel_id", info.channel_id))
+}
+
+/// Stops sending updates over a closed channel. Blocks already received over it stay.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel_id = &msg.channel().endpoint.channel_id;
+    IBC_CHANNELS.remove(deps.storage, channel_id);
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "ibc_channel_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+/// Applies a `BlocklistUpdate` from a counterpart. A failed update is acknowledged with an
+/// error rather than aborting, so the sender learns about it.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let packet = msg.packet;
+
+    do_ibc_packet_receive(deps, env, &packet).or_else(|err| {
+        Ok(IbcReceiveResponse::new()
+            .set_ack(ack_fail(err.to_string()))
+            .add_attribute("method", "receive_blocklist_update")
+            .add_attribute("success", "false")
+            .add_attribute("error", err.to_string()))
+    })
+}
+
+/// Remote blocks are recorded with their origin. A remote unblock only lifts a block that
+/// came from the same channel, so it can never undo a block made on this chain. Updates are
+/// not forwarded to other channels.
+fn do_ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    packet: &IbcPacket,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let channel_id = packet.dest.channel_id.clone();
+    if !IBC_CHANNELS.has(deps.storage, &channel_id) {
+        return Err(ContractError::InvalidIbcChannel {});
+    }
+    let update: BlocklistUpdate = from_binary(&packet.data)?;
+    let human = deps
+        .api
+        .addr_humanize(&CanonicalAddr::from(update.canonical_address.as_slice()))?;
+    let address = normalize(deps.as_ref(), human.as_str())?;
+
+    let applied = if update.blocked {
+        let origin = RemoteOrigin {
+            channel_id: channel_id.clone(),
+            address: update.address.clone(),
+        };
+        block_address(deps.storage, &env, &address, Some(origin))?
+    } else {
+        let origin = BLOCK_RECORDS
+            .may_load(deps.storage, &address)?
+            .and_then(|record| record.origin);
+        let from_channel = matches!(origin, Some(origin) if origin.channel_id == channel_id);
+        from_channel && unblock_address(deps.storage, &env, &address)?
+    };
+
+    Ok(IbcReceiveResponse::new()
+        .set_ack(ack_success())
+        .add_attribute("method", "receive_blocklist_update")
+        .add_attribute("success", "true")
+        .add_attribute("channel_id", channel_id)
+        .add_attribute("address", address)
+        .add_attribute("remote_address", update.address)
+        .add_attribute("blocked", update.blocked.to_string())
+        .add_attribute("applied", applied.to_string()))
+}
+
+/// Updates keep no state on the sending side, so an acknowledgement is only reported.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let update: BlocklistUpdate = from_binary(&msg.original_packet.data)?;
+    let ack: BlocklistAck = from_binary(&msg.acknowledgement.data)?;
+
+    let res = IbcBasicResponse::new()
+        .add_attribute("method", "acknowledge_blocklist_update")
+        .add_attribute("channel_id", &msg.original_packet.src.channel_id)
+        .add_attribute("address", update.address);
+    Ok(match ack {
+        BlocklistAck::Result(_) => res.add_attribute("success", "true"),
+        BlocklistAck::Error(err) => res
+            .add_attribute("success", "false")
+            .add_attribute("error", err),
+    })
+}
+
+/// A timed out update is only reported; blocking or unblocking the address again resends it.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let update: BlocklistUpdate = from_binary(&msg.packet.data)?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "timeout_blocklist_update")
+        .add_attribute("channel_id", &msg.packet.src.channel_id)
+        .add_attribute("address", update.address))
+}
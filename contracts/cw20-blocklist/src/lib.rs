@@ -1,6 +1,9 @@
+mod address;
 pub mod contract;
 mod error;
+pub mod ibc;
 pub mod msg;
+mod signature;
 pub mod state;
 
 pub use crate::error::ContractError;
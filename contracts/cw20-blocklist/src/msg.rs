@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Binary, Uint128, StdResult, StdError};
-use cw20::Expiration;
+use cosmwasm_std::{Binary, Uint128, StdResult, StdError, Timestamp};
+use cw20::{AllowanceInfo, Expiration};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -67,12 +67,226 @@ pub enum ExecuteMsg {
         recipient: String,
         amount: Uint128,
     },
+    /// Mints `amount` to `recipient` locked under `schedule`. Locked tokens can't leave the
+    /// account until they vest. An address has one schedule at a time, so this fails while
+    /// tokens from an earlier one are still locked.
+    MintVested {
+        recipient: String,
+        amount: Uint128,
+        schedule: VestingSchedule,
+    },
     DestroyBlockedFunds {
         address: String,
     },
+    /// Burns the tokens a blocked address has not vested yet and ends its schedule. Tokens
+    /// already vested stay in the account.
+    DestroyUnvestedFunds {
+        address: String,
+    },
     UpdateMinter {
         address: String,
     },
+    /// Sets the hard cap on total supply. `None` removes the cap. Lowering the cap takes effect
+    /// immediately; raising it goes through the timelock while one is configured.
+    UpdateMintCap {
+        cap: Option<Uint128>,
+    },
+    /// Sets the delay, in seconds, sensitive admin actions must wait before they can run, and
+    /// the guardian allowed to cancel them. A zero delay lets those actions run directly.
+    UpdateTimelock {
+        delay: u64,
+        guardian: Option<String>,
+    },
+    /// Queues a sensitive admin action to become executable at `eta`, which must be at least
+    /// the timelock delay away.
+    Schedule {
+        action: TimelockAction,
+        eta: Timestamp,
+    },
+    /// Runs a scheduled action once its `eta` has passed.
+    ExecuteScheduled {
+        id: u64,
+    },
+    /// Drops a scheduled action. Callable by the guardian or the minter.
+    Cancel {
+        id: u64,
+    },
+    /// Sets who approves seizures and minter changes, how many approvals they need, and how
+    /// long a proposal stays open, in seconds. An empty list turns approvals off. While
+    /// approvals are on, this itself can only change through a proposal.
+    UpdateApprovers {
+        approvers: Vec<String>,
+        threshold: u32,
+        voting_period: u64,
+    },
+    /// Opens a proposal for an action that needs approval. Only approvers may propose, and
+    /// the proposer's approval is counted.
+    Propose {
+        action: ProposalAction,
+    },
+    /// Approves or rejects an open proposal. The action runs as soon as the threshold is met.
+    Vote {
+        proposal_id: u64,
+        approve: bool,
+    },
+    /// Grants `role` to an address, or revokes it.
+    SetRole {
+        role: Role,
+        address: String,
+        granted: bool,
+    },
+    /// Asks the compliance team to review the sender's block. Only blocked addresses may ask,
+    /// and only one review per address can be pending at a time.
+    RequestReview {
+        evidence_uri: String,
+    },
+    /// Closes the pending review of `address`, either unblocking it or keeping the block.
+    /// Callable by the compliance role.
+    ResolveReview {
+        address: String,
+        decision: ReviewDecision,
+        note: String,
+    },
+    /// Blocks `old` and moves its balance and the allowances it granted to `new`, for a
+    /// customer who lost the keys to `old`. `IsBlocked` on `old` then points to `new`.
+    RecoverAccount {
+        old: String,
+        new: String,
+        reference: String,
+    },
+    /// Moves `amount` from `from` to `to` regardless of either side's block status, e.g. to
+    /// carry out a court order. Callable by the controller role. The maximum balance still
+    /// applies, tokens still vesting can't be moved, and no fee is charged.
+    ForceTransfer {
+        from: String,
+        to: String,
+        amount: Uint128,
+        legal_reference: String,
+    },
+    /// Asks the operator to mint `amount` to the sender once the payment for `reference` has
+    /// been received off-chain.
+    RequestMint {
+        amount: Uint128,
+        reference: String,
+    },
+    /// Asks the operator to redeem `amount` off-chain. The tokens are held by the contract
+    /// until the request is fulfilled, when they are burned, or rejected, when they are
+    /// returned.
+    RequestRedeem {
+        amount: Uint128,
+        reference: String,
+    },
+    /// Settles a pending request. Callable by the operator role.
+    FulfilRequest {
+        id: u64,
+    },
+    /// Closes a pending request without settling it. Callable by the operator role.
+    RejectRequest {
+        id: u64,
+        reason: String,
+    },
+    /// Moves `amount` from the sender into escrow for `to`. The hold is settled by `Release`,
+    /// which pays `to`, or `Revert`, which returns the tokens to the sender.
+    Hold {
+        to: String,
+        amount: Uint128,
+        expires: Expiration,
+    },
+    /// Pays out a pending hold to its recipient. Callable by the compliance role, or by anyone
+    /// once the hold has expired.
+    Release {
+        id: u64,
+    },
+    /// Returns a pending hold to its sender. Callable by the compliance role.
+    Revert {
+        id: u64,
+    },
+    /// Records a reserve attestation. Callable by the attestor role. `as_of` must be later
+    /// than that of the previous attestation and not in the future.
+    SubmitAttestation {
+        reserves: Uint128,
+        as_of: Timestamp,
+        report_uri: String,
+        hash: String,
+    },
+    /// When enabled, `Mint` cannot raise the total supply above the latest attested reserves.
+    UpdateReserveEnforcement {
+        enabled: bool,
+    },
+    /// Sets the native denom `Wrap` accepts. `None` disables wrapping. The denom can't change
+    /// while wrapped tokens are outstanding.
+    UpdateWrapDenom {
+        denom: Option<String>,
+    },
+    /// Mints tokens 1:1 for the wrapped denom sent along.
+    Wrap {},
+    /// Burns `amount` tokens and returns the same amount of the wrapped denom.
+    Unwrap {
+        amount: Uint128,
+    },
+    /// Allows or forbids opening blocklist channels to the counterpart contract bound to
+    /// `port_id` over `connection_id`.
+    SetIbcPeer {
+        connection_id: String,
+        port_id: String,
+        trusted: bool,
+    },
+    /// Sets the bech32 prefix all addresses must use, e.g. `terra`. `None` disables the check.
+    UpdateAddressPrefix {
+        prefix: Option<String>,
+    },
+    /// Sets the maximum balance a single address may hold. `None` removes the limit.
+    UpdateMaxBalance {
+        max_balance: Option<Uint128>,
+    },
+    /// Exempts an address (exchange, treasury) from the maximum balance, or removes the exemption.
+    SetMaxBalanceExempt {
+        address: String,
+        exempt: bool,
+    },
+    /// Charges a fee of `basis_points` (clamped to `min_fee` and `max_fee`) on transfers and
+    /// sends, paid to `collector`. Set `basis_points` and `min_fee` to zero to disable fees.
+    UpdateFeeConfig {
+        collector: String,
+        basis_points: u16,
+        min_fee: Uint128,
+        max_fee: Option<Uint128>,
+    },
+    /// Exempts an address from transfer fees, or removes the exemption.
+    SetFeeExempt {
+        address: String,
+        exempt: bool,
+    },
+    /// Turns the send whitelist on or off. While on, `Send` and `SendFrom` may only target
+    /// whitelisted contracts.
+    UpdateSendWhitelist {
+        enabled: bool,
+    },
+    /// Adds a receiving contract to the send whitelist, or removes it.
+    SetSendWhitelisted {
+        contract: String,
+        whitelisted: bool,
+    },
+    /// Sets the allowance of `spender` over `owner`'s tokens to `amount`, authorized by the
+    /// owner's signature over a `PermitPayload` instead of a transaction. Anyone may submit it.
+    /// `expires` applies to the permit and to the allowance it grants.
+    Permit {
+        owner: String,
+        spender: String,
+        amount: Uint128,
+        expires: Expiration,
+        nonce: u64,
+        signature: Binary,
+        pubkey: Binary,
+    },
+    /// Runs a `Transfer` or `Send` on behalf of the signer of `payload`, so a relayer can pay
+    /// the gas. The signature covers a `SignedDocument` built from `payload` and `nonce`.
+    ExecuteSigned {
+        payload: SignedPayload,
+        signature: Binary,
+        pubkey: Binary,
+        nonce: u64,
+    },
     /// Implements CW20. Transfer is a base message to move tokens to another account without triggering actions
     Transfer {
         recipient: String,
@@ -122,9 +336,285 @@ pub enum ExecuteMsg {
     },
 }
 
+/// Why a transfer or admin action is refused, in a form integrators can match on. Each
+/// restriction has a stable numeric code, in the spirit of ERC-1404; code 0 means success.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Restriction {
+    Blocked,
+    ZeroAmount,
+    InsufficientBalance,
+    NoAllowance,
+    AllowanceExpired,
+    InsufficientAllowance,
+    MaxBalanceExceeded,
+    ContractNotWhitelisted,
+    Unauthorized,
+    NotBlocked,
+    CapExceeded,
+    BlockedFeeCollector,
+    TimelockRequired,
+    ApprovalRequired,
+    ReservesExceeded,
+    TokensLocked,
+}
+
+/// The code reported when nothing stands in the way.
+pub const RESTRICTION_SUCCESS: u8 = 0;
+
+impl Restriction {
+    pub const ALL: [Restriction; 16] = [
+        Restriction::Blocked,
+        Restriction::ZeroAmount,
+        Restriction::InsufficientBalance,
+        Restriction::NoAllowance,
+        Restriction::AllowanceExpired,
+        Restriction::InsufficientAllowance,
+        Restriction::MaxBalanceExceeded,
+        Restriction::ContractNotWhitelisted,
+        Restriction::Unauthorized,
+        Restriction::NotBlocked,
+        Restriction::CapExceeded,
+        Restriction::BlockedFeeCollector,
+        Restriction::TimelockRequired,
+        Restriction::ApprovalRequired,
+        Restriction::ReservesExceeded,
+        Restriction::TokensLocked,
+    ];
+
+    /// The stable code of this restriction. Codes are never reused or renumbered.
+    pub fn code(&self) -> u8 {
+        match self {
+            Restriction::Blocked => 1,
+            Restriction::ZeroAmount => 2,
+            Restriction::InsufficientBalance => 3,
+            Restriction::NoAllowance => 4,
+            Restriction::AllowanceExpired => 5,
+            Restriction::InsufficientAllowance => 6,
+            Restriction::MaxBalanceExceeded => 7,
+            Restriction::ContractNotWhitelisted => 8,
+            Restriction::Unauthorized => 9,
+            Restriction::NotBlocked => 10,
+            Restriction::CapExceeded => 11,
+            Restriction::BlockedFeeCollector => 12,
+            Restriction::TimelockRequired => 13,
+            Restriction::ApprovalRequired => 14,
+            Restriction::ReservesExceeded => 15,
+            Restriction::TokensLocked => 16,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Restriction> {
+        Restriction::ALL
+            .iter()
+            .copied()
+            .find(|restriction| restriction.code() == code)
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            Restriction::Blocked => "Address is on the blocklist",
+            Restriction::ZeroAmount => "Invalid zero amount",
+            Restriction::InsufficientBalance => "Balance is too low",
+            Restriction::NoAllowance => "No allowance for this account",
+            Restriction::AllowanceExpired => "Allowance is expired",
+            Restriction::InsufficientAllowance => "Allowance is too low",
+            Restriction::MaxBalanceExceeded => "Recipient balance would exceed the maximum balance",
+            Restriction::ContractNotWhitelisted => {
+                "Receiving contract is not on the send whitelist"
+            }
+            Restriction::Unauthorized => "Unauthorized",
+            Restriction::NotBlocked => "Address is not on the blocklist",
+            Restriction::CapExceeded => "Minting cannot exceed the cap",
+            Restriction::BlockedFeeCollector => "Fee collector cannot be on the blocklist",
+            Restriction::TimelockRequired => "This action must be scheduled through the timelock",
+            Restriction::ApprovalRequired => "This action must be proposed and approved",
+            Restriction::ReservesExceeded => "Minting cannot exceed the attested reserves",
+            Restriction::TokensLocked => "Tokens are still vesting",
+        }
+    }
+}
+
+/// When and at what height an address was put on the blocklist.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlockRecord {
+    pub blocked_at: Timestamp,
+    pub height: u64,
+    /// The address the account was recovered to with `RecoverAccount`, if any.
+    #[serde(default)]
+    pub recovered_to: Option<String>,
+    /// The reference given for the recovery.
+    #[serde(default)]
+    pub recovery_reference: Option<String>,
+    /// Set when the block was received from a counterpart contract over IBC.
+    #[serde(default)]
+    pub origin: Option<RemoteOrigin>,
+}
+
+/// Where a block received over IBC came from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RemoteOrigin {
+    /// Our end of the channel the update arrived on.
+    pub channel_id: String,
+    /// The address as it is blocked on the remote chain.
+    pub address: String,
+}
+
+/// Admin actions that must wait out the timelock delay while one is configured.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockAction {
+    RemoveFromBlockedList {
+        address: String,
+    },
+    UpdateMinter {
+        address: String,
+    },
+    /// Only raising the cap is timelocked; lowering it may be done directly.
+    UpdateMintCap {
+        cap: Option<Uint128>,
+    },
+    UpdateTimelock {
+        delay: u64,
+        guardian: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Resolves block reviews.
+    Compliance,
+    /// Moves tokens between accounts with `ForceTransfer`.
+    Controller,
+    /// Submits reserve attestations.
+    Attestor,
+    /// Fulfils or rejects mint and redeem requests.
+    Operator,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Compliance => "compliance",
+            Role::Controller => "controller",
+            Role::Attestor => "attestor",
+            Role::Operator => "operator",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDecision {
+    Unblock,
+    Uphold,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending,
+    Unblocked,
+    Upheld,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::Pending => "pending",
+            ReviewStatus::Unblocked => "unblocked",
+            ReviewStatus::Upheld => "upheld",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    Mint,
+    Redeem,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestStatus {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+impl RequestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestStatus::Pending => "pending",
+            RequestStatus::Fulfilled => "fulfilled",
+            RequestStatus::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldStatus {
+    Pending,
+    Released,
+    Reverted,
+}
+
+/// Actions that need M-of-N approval while approvers are configured.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalAction {
+    DestroyBlockedFunds {
+        address: String,
+    },
+    DestroyUnvestedFunds {
+        address: String,
+    },
+    /// Goes through the timelock after approval while one is configured.
+    UpdateMinter {
+        address: String,
+    },
+    UpdateApprovers {
+        approvers: Vec<String>,
+        threshold: u32,
+        voting_period: u64,
+    },
+    RecoverAccount {
+        old: String,
+        new: String,
+        reference: String,
+    },
+    /// Only granting the controller role needs approval, any role change can be proposed.
+    SetRole {
+        role: Role,
+        address: String,
+        granted: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Open,
+    Executed,
+    Rejected,
+    Expired,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct BlockedResponse {
     pub blocked: bool,
+    /// The replacement address of an account recovered with `RecoverAccount`.
+    pub recovered_to: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MaxBalanceResponse {
+    /// The limit that applies to the address, `None` if it may hold any amount.
+    pub max_balance: Option<Uint128>,
+    pub exempt: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -160,7 +650,453 @@ pub enum QueryMsg {
     /// Implements CW20 "mintable" extension. 
     /// Returns who can mint and the hard cap on maximum tokens after minting.
     Minter {},
+    /// Returns the maximum balance that applies to the given address.
+    MaxBalance {
+        address: String,
+    },
+    /// Returns the current transfer fee settings, if any.
+    FeeConfig {},
+    /// Returns the balance of the given address at the start of the block at `height`.
+    BalanceAt {
+        address: String,
+        height: u64,
+    },
+    /// Returns the total supply at the start of the block at `height`.
+    TotalSupplyAt {
+        height: u64,
+    },
+    /// Returns whether the send whitelist is enforced and the whitelisted contracts.
+    /// Supports pagination.
+    SendWhitelist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the timelock delay and guardian.
+    Timelock {},
+    /// Returns scheduled actions that have been neither executed nor cancelled.
+    /// Supports pagination.
+    PendingActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the approvers, threshold and voting period.
+    Approvers {},
+    Proposal {
+        proposal_id: u64,
+    },
+    /// Returns proposals that can still be voted on, the first to expire first. Supports
+    /// pagination.
+    OpenProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the votes cast on a proposal. Supports pagination.
+    ProposalVotes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Runs the checks a `Transfer` (or a `TransferFrom` by `spender`) would go through
+    /// without moving any tokens, and reports whether it would succeed.
+    CanTransfer {
+        from: String,
+        to: String,
+        amount: Uint128,
+        spender: Option<String>,
+    },
+    /// Returns the restriction behind a numeric code, and its message.
+    RestrictionMessage {
+        code: u8,
+    },
+    /// Returns everything that decides whether the address can move tokens: its balance,
+    /// blocklist state, frozen amount and allowances.
+    AccountStatus {
+        address: String,
+    },
+    /// Returns supply, holder and blocklist totals.
+    Stats {},
+    /// Returns the IBC channels blocklist updates are exchanged over.
+    IbcChannels {},
+    /// Returns the wrapped denom and the reserves backing wrapped tokens.
+    Reserves {},
+    /// Returns the latest reserve attestation and whether minting is held to it.
+    LatestAttestation {},
+    /// Returns the vesting schedule of `address` and how much of it is still locked.
+    VestingSchedule {
+        address: String,
+    },
+    /// Returns the nonce the next message signed by `address` must carry.
+    Nonce {
+        address: String,
+    },
+    /// Returns a hold.
+    Hold {
+        id: u64,
+    },
+    /// Returns the holds placed by `sender`. Supports pagination.
+    HoldsBySender {
+        sender: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the holds in favour of `recipient`. Supports pagination.
+    HoldsByRecipient {
+        recipient: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns a mint or redeem request.
+    Request {
+        id: u64,
+    },
+    /// Returns the mint and redeem requests with `status`. Supports pagination.
+    RequestsByStatus {
+        status: RequestStatus,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the mint and redeem requests opened by `requester`. Supports pagination.
+    RequestsByRequester {
+        requester: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns all reserve attestations, oldest first. Supports pagination.
+    Attestations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the addresses holding `role`. Supports pagination.
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns every review requested by `address`, oldest first. Supports pagination.
+    Reviews {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns reviews in the given state, oldest first. Supports pagination.
+    ReviewsByStatus {
+        status: ReviewStatus,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalSupplyResponse {
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SendWhitelistResponse {
+    pub enabled: bool,
+    pub contracts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockResponse {
+    /// Seconds a sensitive action must wait between scheduling and execution.
+    pub delay: u64,
+    pub guardian: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingAction {
+    pub id: u64,
+    pub action: TimelockAction,
+    pub eta: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingActionsResponse {
+    pub actions: Vec<PendingAction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApproversResponse {
+    pub approvers: Vec<String>,
+    pub threshold: u32,
+    pub voting_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse {
+    pub id: u64,
+    pub action: ProposalAction,
+    pub proposer: String,
+    pub status: ProposalStatus,
+    pub expires: Timestamp,
+    /// Approvals needed, fixed when the proposal was opened.
+    pub threshold: u32,
+    pub approvals: u32,
+    pub rejections: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalsResponse {
+    pub proposals: Vec<ProposalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteInfo {
+    pub voter: String,
+    pub approve: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalVotesResponse {
+    pub votes: Vec<VoteInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReviewInfo {
+    pub id: u64,
+    pub address: String,
+    pub evidence_uri: String,
+    pub status: ReviewStatus,
+    pub requested_at: Timestamp,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<Timestamp>,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReviewsResponse {
+    pub reviews: Vec<ReviewInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CanTransferResponse {
+    pub allowed: bool,
+    /// The restriction code, `0` when the transfer would succeed.
+    pub code: u8,
+    /// Set when the transfer would be refused.
+    pub reason: Option<Restriction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RestrictionMessageResponse {
+    pub code: u8,
+    /// `None` for the success code.
+    pub restriction: Option<Restriction>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceivedAllowance {
+    pub owner: String,
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AccountStatusResponse {
+    pub balance: Uint128,
+    pub blocked: bool,
+    /// Set while the address is blocked, and kept for a recovered address after it is
+    /// unblocked. Addresses blocked before records were kept have none.
+    pub block_record: Option<BlockRecord>,
+    /// The part of the balance the address cannot move: all of it while blocked, otherwise
+    /// the tokens still vesting.
+    pub frozen: Uint128,
+    /// Up to 30 unexpired allowances the address has granted; see `AllAllowances` for the
+    /// rest.
+    pub allowances_granted: Vec<AllowanceInfo>,
+    /// Up to 30 unexpired allowances the address has received.
+    pub allowances_received: Vec<ReceivedAllowance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub total_supply: Uint128,
+    /// Accounts with a non-zero balance.
+    pub holders: u64,
+    pub blocked_addresses: u64,
+    /// Sum of the balances of blocked addresses.
+    pub blocked_balance: Uint128,
+    /// Total removed from blocked addresses by `DestroyBlockedFunds` and `DestroyUnvestedFunds`.
+    pub destroyed: Uint128,
+    pub minted: Uint128,
+    pub redeemed: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
+
+/// An open channel to a counterpart contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelInfo {
+    pub channel_id: String,
+    pub connection_id: String,
+    pub counterparty_port_id: String,
+    pub counterparty_channel_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcChannelsResponse {
+    pub channels: Vec<ChannelInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservesResponse {
+    /// `None` while wrapping is disabled.
+    pub denom: Option<String>,
+    /// Tokens minted by `Wrap` and not yet returned by `Unwrap`.
+    pub wrapped: Uint128,
+    /// The contract's bank balance of `denom`.
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AttestationInfo {
+    pub id: u64,
+    pub reserves: Uint128,
+    pub as_of: Timestamp,
+    pub report_uri: String,
+    pub hash: String,
+    pub submitted_by: String,
+    pub submitted_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LatestAttestationResponse {
+    pub attestation: Option<AttestationInfo>,
+    /// Whether `Mint` is held to the attested reserves.
+    pub enforced: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AttestationsResponse {
+    pub attestations: Vec<AttestationInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RequestInfo {
+    pub id: u64,
+    pub kind: RequestKind,
+    pub requester: String,
+    pub amount: Uint128,
+    pub reference: String,
+    pub status: RequestStatus,
+    pub created_at: Timestamp,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<Timestamp>,
+    /// Why the request was rejected.
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RequestsResponse {
+    pub requests: Vec<RequestInfo>,
+}
+
+/// How tokens minted with `MintVested` unlock, measured against block time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VestingSchedule {
+    /// Everything unlocks at `release_at`.
+    Cliff { release_at: Timestamp },
+    /// Unlocks evenly from `start` to `end`. If `cliff` is set, nothing unlocks before it and
+    /// what accrued since `start` unlocks at once when it passes.
+    Linear {
+        start: Timestamp,
+        end: Timestamp,
+        cliff: Option<Timestamp>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingScheduleResponse {
+    /// `None` if nothing was minted to the address with `MintVested`.
+    pub schedule: Option<VestingSchedule>,
+    /// The amount minted under the schedule.
+    pub amount: Uint128,
+    pub vested: Uint128,
+    pub locked: Uint128,
+    /// Whether vesting is stopped because the address is blocked.
+    pub paused: bool,
+    /// Seconds vesting has been held back by blocks, the current one included.
+    pub paused_for: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HoldInfo {
+    pub id: u64,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub expires: Expiration,
+    pub status: HoldStatus,
+    pub created_at: Timestamp,
+    /// Who released or reverted the hold.
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HoldsResponse {
+    pub holds: Vec<HoldInfo>,
+}
+
+/// The document an owner signs to authorize a `Permit`. The signature covers the SHA-256 hash
+/// of its JSON encoding, with the fields in this order and addresses in lowercase.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PermitPayload {
+    pub chain_id: String,
+    pub contract: String,
+    pub owner: String,
+    pub spender: String,
+    pub amount: Uint128,
+    pub expires: Expiration,
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NonceResponse {
+    pub nonce: u64,
+}
+
+/// A transfer authorized by its signer's signature rather than their transaction.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedPayload {
+    pub signer: String,
+    pub msg: SignedMsg,
+    /// When the signature stops being accepted.
+    pub expires: Expiration,
+    /// Paid in this token by the signer to the account submitting the message.
+    pub relayer_fee: Option<Uint128>,
+}
+
+/// The messages `ExecuteSigned` can run on behalf of a signer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignedMsg {
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+    },
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+}
+
+/// The document signed for `ExecuteSigned`. The signature covers the SHA-256 hash of its JSON
+/// encoding, with the fields in this order and addresses in lowercase.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedDocument {
+    pub chain_id: String,
+    pub contract: String,
+    pub payload: SignedPayload,
+    pub nonce: u64,
+}
@@ -0,0 +1,54 @@
+use bech32::FromBase32;
+use cosmwasm_std::{Addr, Binary, Deps, Storage};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::state::NONCES;
+
+/// Checks that `signature` is a signature by `pubkey` over the SHA-256 hash of `payload`, and
+/// that `pubkey` belongs to `signer`.
+///
+/// An account address is the RIPEMD-160 hash of the SHA-256 hash of its compressed public key,
+/// so the key is matched against the data part of the bech32 address rather than through
+/// `addr_canonicalize`, which need not agree with that encoding.
+pub(crate) fn verify_signature(
+    deps: Deps,
+    signer: &Addr,
+    payload: &[u8],
+    signature: &Binary,
+    pubkey: &Binary,
+) -> Result<(), ContractError> {
+    let (_, data, _) =
+        bech32::decode(signer.as_str()).map_err(|_| ContractError::PubkeyMismatch {})?;
+    let account = Vec::<u8>::from_base32(&data).map_err(|_| ContractError::PubkeyMismatch {})?;
+    let key_hash = Ripemd160::digest(&Sha256::digest(pubkey.as_slice()));
+    if account != key_hash[..] {
+        return Err(ContractError::PubkeyMismatch {});
+    }
+
+    let hash = Sha256::digest(payload);
+    let valid = deps
+        .api
+        .secp256k1_verify(&hash, signature.as_slice(), pubkey.as_slice())
+        .map_err(|_| ContractError::InvalidSignature {})?;
+    if !valid {
+        return Err(ContractError::InvalidSignature {});
+    }
+    Ok(())
+}
+
+/// Uses up `nonce` for `signer`. Nonces start at zero and must be used in order, so each
+/// signed message can be submitted once.
+pub(crate) fn use_nonce(
+    storage: &mut dyn Storage,
+    signer: &Addr,
+    nonce: u64,
+) -> Result<(), ContractError> {
+    let expected = NONCES.may_load(storage, signer)?.unwrap_or_default();
+    if nonce != expected {
+        return Err(ContractError::InvalidNonce { expected });
+    }
+    NONCES.save(storage, signer, &(expected + 1))?;
+    Ok(())
+}
@@ -1,4 +1,314 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cw20::Expiration;
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotItem, SnapshotMap, Strategy,
+};
+
+use crate::msg::{
+    BlockRecord, ChannelInfo, HoldStatus, ProposalAction, ProposalStatus, RequestKind,
+    RequestStatus, ReviewStatus, TimelockAction, VestingSchedule,
+};
 
 pub const BLOCKED: Map<&Addr, bool> = Map::new("blocked");
+/// Details of each current block, removed when the address is unblocked.
+pub const BLOCK_RECORDS: Map<&Addr, BlockRecord> = Map::new("block_records");
+
+/// Human-readable bech32 prefix every address must use, e.g. `terra`. Unset skips the check.
+pub const ADDRESS_PREFIX: Item<String> = Item::new("address_prefix");
+
+/// Maximum balance a single address may hold. Unset means no limit.
+pub const MAX_BALANCE: Item<Uint128> = Item::new("max_balance");
+/// Addresses (exchanges, treasury) that are not subject to `MAX_BALANCE`.
+pub const MAX_BALANCE_EXEMPT: Map<&Addr, bool> = Map::new("max_balance_exempt");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeConfig {
+    /// Receives the fees charged on transfers and sends.
+    pub collector: Addr,
+    /// Fee as a fraction of the transferred amount, in 1/10000ths.
+    pub basis_points: u16,
+    /// Lower bound applied to the computed fee.
+    pub min_fee: Uint128,
+    /// Upper bound applied to the computed fee, if any.
+    pub max_fee: Option<Uint128>,
+}
+
+pub const FEE_CONFIG: Item<FeeConfig> = Item::new("fee_config");
+/// Addresses whose transfers, sent or received, are not charged a fee.
+pub const FEE_EXEMPT: Map<&Addr, bool> = Map::new("fee_exempt");
+
+/// When set, `Send` and `SendFrom` may only target contracts in `SEND_WHITELIST`.
+pub const SEND_WHITELIST_ENABLED: Item<bool> = Item::new("send_whitelist_enabled");
+/// Vetted receiving contracts (DEXes, vaults).
+pub const SEND_WHITELIST: Map<&Addr, bool> = Map::new("send_whitelist");
+
+/// Mirror of the cw20-base `BALANCES`, recording the history needed for `BalanceAt`.
+pub const BALANCE_SNAPSHOTS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "balance_snapshots",
+    "balance_snapshots__checkpoints",
+    "balance_snapshots__changelog",
+    Strategy::EveryBlock,
+);
+/// Mirror of `TokenInfo::total_supply`, recording the history needed for `TotalSupplyAt`.
+pub const TOTAL_SUPPLY_SNAPSHOTS: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_supply_snapshots",
+    "total_supply_snapshots__checkpoints",
+    "total_supply_snapshots__changelog",
+    Strategy::EveryBlock,
+);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockConfig {
+    /// Seconds between scheduling a sensitive action and being able to execute it.
+    pub delay: u64,
+    /// May cancel scheduled actions alongside the minter.
+    pub guardian: Option<Addr>,
+}
+
+pub const TIMELOCK: Item<TimelockConfig> = Item::new("timelock");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledAction {
+    pub action: TimelockAction,
+    pub eta: Timestamp,
+    /// Set when an approved proposal queued the action.
+    #[serde(default)]
+    pub approved: bool,
+}
+
+/// Scheduled actions by id, removed once executed or cancelled.
+pub const SCHEDULED_ACTIONS: Map<u64, ScheduledAction> = Map::new("scheduled_actions");
+/// The id handed to the next scheduled action.
+pub const NEXT_ACTION_ID: Item<u64> = Item::new("next_action_id");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalConfig {
+    pub approvers: Vec<Addr>,
+    pub threshold: u32,
+    /// Seconds a proposal stays open for voting.
+    pub voting_period: u64,
+}
+
+/// Unset, or with no approvers, means the minter acts alone.
+pub const APPROVAL_CONFIG: Item<ApprovalConfig> = Item::new("approval_config");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Proposal {
+    pub action: ProposalAction,
+    pub proposer: Addr,
+    /// `Open` until executed or rejected; expiry is derived from `expires`.
+    pub status: ProposalStatus,
+    pub expires: Timestamp,
+    pub threshold: u32,
+    pub approvals: u32,
+    pub rejections: u32,
+}
+
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+/// Open proposals by expiry time in seconds and id, so listing them starts at the current
+/// time instead of reading every past proposal.
+pub const OPEN_PROPOSALS: Map<(u64, u64), bool> = Map::new("open_proposals");
+/// The id handed to the next proposal.
+pub const NEXT_PROPOSAL_ID: Item<u64> = Item::new("next_proposal_id");
+/// Votes by proposal id and approver, `true` for approval.
+pub const VOTES: Map<(u64, &Addr), bool> = Map::new("votes");
+
+/// Role holders by role name and address.
+pub const ROLES: Map<(&str, &Addr), bool> = Map::new("roles");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Review {
+    pub address: Addr,
+    pub evidence_uri: String,
+    pub status: ReviewStatus,
+    pub requested_at: Timestamp,
+    pub resolved_by: Option<Addr>,
+    pub resolved_at: Option<Timestamp>,
+    pub note: Option<String>,
+}
+
+pub struct ReviewIndexes<'a> {
+    pub address: MultiIndex<'a, Addr, Review, u64>,
+    pub status: MultiIndex<'a, String, Review, u64>,
+}
+
+impl<'a> IndexList<Review> for ReviewIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Review>> + '_> {
+        let v: Vec<&dyn Index<Review>> = vec![&self.address, &self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Block reviews by id, indexed by the reviewed address and by status.
+pub fn reviews<'a>() -> IndexedMap<'a, u64, Review, ReviewIndexes<'a>> {
+    let indexes = ReviewIndexes {
+        address: MultiIndex::new(
+            |review| review.address.clone(),
+            "reviews",
+            "reviews__address",
+        ),
+        status: MultiIndex::new(
+            |review| review.status.as_str().to_string(),
+            "reviews",
+            "reviews__status",
+        ),
+    };
+    IndexedMap::new("reviews", indexes)
+}
+
+/// The id handed to the next review.
+pub const NEXT_REVIEW_ID: Item<u64> = Item::new("next_review_id");
+
+/// Running totals for dashboards, kept up to date so they need no scan of all accounts.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Stats {
+    /// Accounts with a non-zero balance.
+    pub holders: u64,
+    pub blocked_addresses: u64,
+    /// Sum of the balances of blocked addresses.
+    pub blocked_balance: Uint128,
+    /// Total removed by `DestroyBlockedFunds` and `DestroyUnvestedFunds`.
+    pub destroyed: Uint128,
+    pub minted: Uint128,
+    pub redeemed: Uint128,
+}
+
+pub const STATS: Item<Stats> = Item::new("stats");
+
+/// Allowance pairs by spender and owner, as cw20-base only indexes them by owner. Entries may
+/// outlive the allowance they point to and must be checked against `ALLOWANCES`.
+pub const ALLOWANCE_SPENDERS: Map<(&Addr, &Addr), bool> = Map::new("allowance_spenders");
+
+/// Counterpart contracts, by connection id and port, that may open blocklist channels.
+pub const IBC_PEERS: Map<(&str, &str), bool> = Map::new("ibc_peers");
+
+/// Open blocklist channels by our channel id.
+pub const IBC_CHANNELS: Map<&str, ChannelInfo> = Map::new("ibc_channels");
+
+/// The native denom accepted by `Wrap`.
+pub const WRAP_DENOM: Item<String> = Item::new("wrap_denom");
+
+/// Tokens minted by `Wrap` and not yet unwrapped, i.e. the reserves owed to holders.
+pub const WRAPPED: Item<Uint128> = Item::new("wrapped");
+
+/// A reserve attestation as submitted by an attestor.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Attestation {
+    pub reserves: Uint128,
+    pub as_of: Timestamp,
+    pub report_uri: String,
+    pub hash: String,
+    pub submitted_by: Addr,
+    pub submitted_at: Timestamp,
+}
+
+pub const ATTESTATIONS: Map<u64, Attestation> = Map::new("attestations");
+
+/// The id handed to the next attestation.
+pub const NEXT_ATTESTATION_ID: Item<u64> = Item::new("next_attestation_id");
+
+/// Whether `Mint` is held to the latest attested reserves.
+pub const ENFORCE_RESERVES: Item<bool> = Item::new("enforce_reserves");
+
+/// A mint or redeem request awaiting off-chain settlement.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IssuanceRequest {
+    pub kind: RequestKind,
+    pub requester: Addr,
+    pub amount: Uint128,
+    pub reference: String,
+    pub status: RequestStatus,
+    pub created_at: Timestamp,
+    pub resolved_by: Option<Addr>,
+    pub resolved_at: Option<Timestamp>,
+    pub reason: Option<String>,
+}
+
+pub struct IssuanceRequestIndexes<'a> {
+    pub requester: MultiIndex<'a, Addr, IssuanceRequest, u64>,
+    pub status: MultiIndex<'a, String, IssuanceRequest, u64>,
+}
+
+impl<'a> IndexList<IssuanceRequest> for IssuanceRequestIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<IssuanceRequest>> + '_> {
+        let v: Vec<&dyn Index<IssuanceRequest>> = vec![&self.requester, &self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Mint and redeem requests by id, indexed by requester and by status.
+pub fn issuance_requests<'a>() -> IndexedMap<'a, u64, IssuanceRequest, IssuanceRequestIndexes<'a>> {
+    let indexes = IssuanceRequestIndexes {
+        requester: MultiIndex::new(
+            |request| request.requester.clone(),
+            "requests",
+            "requests__requester",
+        ),
+        status: MultiIndex::new(
+            |request| request.status.as_str().to_string(),
+            "requests",
+            "requests__status",
+        ),
+    };
+    IndexedMap::new("requests", indexes)
+}
+
+/// The id handed to the next mint or redeem request.
+pub const NEXT_REQUEST_ID: Item<u64> = Item::new("next_request_id");
+
+/// Tokens minted to an address with `MintVested`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vesting {
+    pub amount: Uint128,
+    pub schedule: VestingSchedule,
+    /// Seconds vesting was stopped by blocks that have since been lifted.
+    pub paused_for: u64,
+    /// When the current block of the holder stopped vesting.
+    pub paused_at: Option<Timestamp>,
+}
+
+pub const VESTING: Map<&Addr, Vesting> = Map::new("vesting");
+
+/// Tokens escrowed by the contract until they are released to `recipient` or reverted to
+/// `sender`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Hold {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub expires: Expiration,
+    pub status: HoldStatus,
+    pub created_at: Timestamp,
+    pub resolved_by: Option<Addr>,
+    pub resolved_at: Option<Timestamp>,
+}
+
+pub struct HoldIndexes<'a> {
+    pub sender: MultiIndex<'a, Addr, Hold, u64>,
+    pub recipient: MultiIndex<'a, Addr, Hold, u64>,
+}
+
+impl<'a> IndexList<Hold> for HoldIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Hold>> + '_> {
+        let v: Vec<&dyn Index<Hold>> = vec![&self.sender, &self.recipient];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Holds by id, indexed by sender and by recipient.
+pub fn holds<'a>() -> IndexedMap<'a, u64, Hold, HoldIndexes<'a>> {
+    let indexes = HoldIndexes {
+        sender: MultiIndex::new(|hold| hold.sender.clone(), "holds", "holds__sender"),
+        recipient: MultiIndex::new(|hold| hold.recipient.clone(), "holds", "holds__recipient"),
+    };
+    IndexedMap::new("holds", indexes)
+}
+
+/// The id handed to the next hold.
+pub const NEXT_HOLD_ID: Item<u64> = Item::new("next_hold_id");
+
+/// The nonce the next signed message of each address must carry.
+pub const NONCES: Map<&Addr, u64> = Map::new("nonces");
@@ -0,0 +1,340 @@
+//! Blocklist propagation between two deployments, with a minimal in-process relayer standing in
+//! for real IBC.
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, CosmosMsg, IbcAcknowledgement, IbcChannel, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcEndpoint, IbcMsg, IbcOrder, IbcPacket, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, OwnedDeps, Response,
+};
+
+use cw20_blocklist::contract::{execute, instantiate, query};
+use cw20_blocklist::ibc::{
+    ibc_channel_connect, ibc_channel_open, ibc_packet_ack, ibc_packet_receive, BlocklistAck,
+    BlocklistUpdate, IBC_VERSION,
+};
+use cw20_blocklist::msg::{
+    AccountStatusResponse, BlockedResponse, ExecuteMsg, IbcChannelsResponse, InstantiateMsg,
+    ProposalAction, QueryMsg, RemoteOrigin,
+};
+use cw20_blocklist::ContractError;
+
+const MINTER: &str = "creator";
+
+struct Chain {
+    deps: OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    port: &'static str,
+    connection: &'static str,
+    channel: &'static str,
+}
+
+impl Chain {
+    /// Instantiates the token and trusts `peer_port` over this chain's connection.
+    fn new(
+        port: &'static str,
+        connection: &'static str,
+        channel: &'static str,
+        peer_port: &str,
+    ) -> Self {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            name: "Auto Gen".to_string(),
+            symbol: "AUTO".to_string(),
+            decimals: 6,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), msg).unwrap();
+        let mut chain = Chain {
+            deps,
+            port,
+            connection,
+            channel,
+        };
+        chain
+            .execute(ExecuteMsg::SetIbcPeer {
+                connection_id: connection.to_string(),
+                port_id: peer_port.to_string(),
+                trusted: true,
+            })
+            .unwrap();
+        chain
+    }
+
+    fn execute(&mut self, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(self.deps.as_mut(), mock_env(), mock_info(MINTER, &[]), msg)
+    }
+
+    fn block(&mut self, address: &str) -> Response {
+        self.execute(ExecuteMsg::AddToBlockedList {
+            address: address.to_string(),
+        })
+        .unwrap()
+    }
+
+    fn unblock(&mut self, address: &str) -> Response {
+        self.execute(ExecuteMsg::RemoveFromBlockedList {
+            address: address.to_string(),
+        })
+        .unwrap()
+    }
+
+    fn is_blocked(&self, address: &str) -> BlockedResponse {
+        let msg = QueryMsg::IsBlocked {
+            address: address.to_string(),
+        };
+        from_binary(&query(self.deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+    }
+
+    fn origin(&self, address: &str) -> Option<RemoteOrigin> {
+        let msg = QueryMsg::AccountStatus {
+            address: address.to_string(),
+        };
+        let status: AccountStatusResponse =
+            from_binary(&query(self.deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        status.block_record.and_then(|record| record.origin)
+    }
+
+    fn endpoint(&self) -> IbcEndpoint {
+        IbcEndpoint {
+            port_id: self.port.to_string(),
+            channel_id: self.channel.to_string(),
+        }
+    }
+
+    fn channel_to(&self, other: &Chain, version: &str) -> IbcChannel {
+        IbcChannel::new(
+            self.endpoint(),
+            other.endpoint(),
+            IbcOrder::Unordered,
+            version,
+            self.connection,
+        )
+    }
+}
+
+/// Runs the four-step channel handshake between `a` and `b`.
+fn connect(a: &mut Chain, b: &mut Chain) {
+    let channel_a = a.channel_to(b, IBC_VERSION);
+    let channel_b = b.channel_to(a, IBC_VERSION);
+
+    ibc_channel_open(
+        a.deps.as_mut(),
+        mock_env(),
+        IbcChannelOpenMsg::new_init(channel_a.clone()),
+    )
+    .unwrap();
+    ibc_channel_open(
+        b.deps.as_mut(),
+        mock_env(),
+        IbcChannelOpenMsg::new_try(channel_b.clone(), IBC_VERSION),
+    )
+    .unwrap();
+    ibc_channel_connect(
+        a.deps.as_mut(),
+        mock_env(),
+        IbcChannelConnectMsg::new_ack(channel_a, IBC_VERSION),
+    )
+    .unwrap();
+    ibc_channel_connect(
+        b.deps.as_mut(),
+        mock_env(),
+        IbcChannelConnectMsg::new_confirm(channel_b),
+    )
+    .unwrap();
+}
+
+/// Delivers every packet in `res` from `from` to `to`, hands the acknowledgements back to
+/// `from` and returns them.
+fn relay(from: &mut Chain, to: &mut Chain, res: Response) -> Vec<BlocklistAck> {
+    let mut acks = vec![];
+    for sub_msg in res.messages {
+        let (channel_id, data, timeout) = match sub_msg.msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket {
+                channel_id,
+                data,
+                timeout,
+            }) => (channel_id, data, timeout),
+            _ => continue,
+        };
+        assert_eq!(channel_id, from.channel);
+        let packet = IbcPacket::new(data, from.endpoint(), to.endpoint(), 1, timeout);
+
+        let received = ibc_packet_receive(
+            to.deps.as_mut(),
+            mock_env(),
+            IbcPacketReceiveMsg::new(packet.clone()),
+        )
+        .unwrap();
+        assert!(received.messages.is_empty(), "updates are not forwarded");
+        let ack = received.acknowledgement;
+        ibc_packet_ack(
+            from.deps.as_mut(),
+            mock_env(),
+            IbcPacketAckMsg::new(IbcAcknowledgement::new(ack.clone()), packet),
+        )
+        .unwrap();
+        acks.push(from_binary(&ack).unwrap());
+    }
+    acks
+}
+
+fn setup() -> (Chain, Chain) {
+    let mut a = Chain::new("wasm.token-a", "connection-0", "channel-0", "wasm.token-b");
+    let mut b = Chain::new("wasm.token-b", "connection-3", "channel-5", "wasm.token-a");
+    connect(&mut a, &mut b);
+    (a, b)
+}
+
+#[test]
+fn handshake_requires_trusted_peer_and_version() {
+    let mut a = Chain::new("wasm.token-a", "connection-0", "channel-0", "wasm.token-b");
+    let b = Chain::new("wasm.token-b", "connection-3", "channel-5", "wasm.token-a");
+
+    let msg = IbcChannelOpenMsg::new_init(a.channel_to(&b, "ics20-1"));
+    let err = ibc_channel_open(a.deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidIbcChannel {});
+
+    let msg = IbcChannelOpenMsg::new_try(a.channel_to(&b, IBC_VERSION), "ics20-1");
+    let err = ibc_channel_open(a.deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidIbcChannel {});
+
+    a.execute(ExecuteMsg::SetIbcPeer {
+        connection_id: "connection-0".to_string(),
+        port_id: "wasm.token-b".to_string(),
+        trusted: false,
+    })
+    .unwrap();
+    let msg = IbcChannelOpenMsg::new_init(a.channel_to(&b, IBC_VERSION));
+    let err = ibc_channel_open(a.deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(err, ContractError::UntrustedIbcPeer {});
+
+    // Only the minter manages peers.
+    let msg = ExecuteMsg::SetIbcPeer {
+        connection_id: "connection-0".to_string(),
+        port_id: "wasm.token-b".to_string(),
+        trusted: true,
+    };
+    let err = execute(a.deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn blocks_follow_the_funds() {
+    let (mut a, mut b) = setup();
+
+    let res: IbcChannelsResponse =
+        from_binary(&query(a.deps.as_ref(), mock_env(), QueryMsg::IbcChannels {}).unwrap())
+            .unwrap();
+    assert_eq!(res.channels.len(), 1);
+    assert_eq!(res.channels[0].counterparty_channel_id, "channel-5");
+
+    let res = a.block("alice");
+    let acks = relay(&mut a, &mut b, res);
+    assert_eq!(acks, vec![BlocklistAck::Result(b"1".into())]);
+
+    assert!(b.is_blocked("alice").blocked);
+    assert_eq!(
+        b.origin("alice"),
+        Some(RemoteOrigin {
+            channel_id: "channel-5".to_string(),
+            address: "alice".to_string(),
+        })
+    );
+    // Blocked remotely, the address can't move funds here either.
+    let msg = ExecuteMsg::Transfer {
+        recipient: "bob".to_string(),
+        amount: 1u128.into(),
+    };
+    let err = execute(b.deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Blocked {});
+
+    // Blocking again sends nothing.
+    assert!(a.block("alice").messages.is_empty());
+
+    let res = a.unblock("alice");
+    relay(&mut a, &mut b, res);
+    assert!(!b.is_blocked("alice").blocked);
+}
+
+#[test]
+fn remote_unblock_keeps_local_blocks() {
+    let (mut a, mut b) = setup();
+
+    // Blocked on both chains independently.
+    b.block("bob");
+    let res = a.block("bob");
+    relay(&mut a, &mut b, res);
+    assert_eq!(b.origin("bob"), None);
+
+    // Blocked remotely first, then taken over by a local block, which is sent back.
+    let res = a.block("carol");
+    relay(&mut a, &mut b, res);
+    let res = b.block("carol");
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(b.origin("carol"), None);
+
+    for address in ["bob", "carol"] {
+        let res = a.unblock(address);
+        relay(&mut a, &mut b, res);
+        assert!(b.is_blocked(address).blocked);
+    }
+}
+
+#[test]
+fn approved_recovery_is_sent() {
+    let (mut a, mut b) = setup();
+    a.execute(ExecuteMsg::UpdateApprovers {
+        approvers: vec!["alice".to_string(), "bob".to_string()],
+        threshold: 2,
+        voting_period: 3600,
+    })
+    .unwrap();
+
+    let msg = ExecuteMsg::Propose {
+        action: ProposalAction::RecoverAccount {
+            old: "lost".to_string(),
+            new: "found".to_string(),
+            reference: "ticket 4411".to_string(),
+        },
+    };
+    let res = execute(a.deps.as_mut(), mock_env(), mock_info("alice", &[]), msg).unwrap();
+    assert!(res.messages.is_empty());
+    let msg = ExecuteMsg::Vote {
+        proposal_id: 0,
+        approve: true,
+    };
+    let res = execute(a.deps.as_mut(), mock_env(), mock_info("bob", &[]), msg).unwrap();
+    let acks = relay(&mut a, &mut b, res);
+    assert_eq!(acks, vec![BlocklistAck::Result(b"1".into())]);
+
+    assert!(a.is_blocked("lost").blocked);
+    assert!(b.is_blocked("lost").blocked);
+}
+
+#[test]
+fn bad_packets_are_acknowledged_with_an_error() {
+    let (a, mut b) = setup();
+
+    let update = BlocklistUpdate {
+        address: "alice".to_string(),
+        canonical_address: b"x".into(),
+        blocked: true,
+    };
+    let packet = IbcPacket::new(
+        to_binary(&update).unwrap(),
+        a.endpoint(),
+        b.endpoint(),
+        1,
+        mock_env().block.time.plus_seconds(60).into(),
+    );
+    let res = ibc_packet_receive(
+        b.deps.as_mut(),
+        mock_env(),
+        IbcPacketReceiveMsg::new(packet),
+    )
+    .unwrap();
+    let ack: BlocklistAck = from_binary(&res.acknowledgement).unwrap();
+    assert!(matches!(ack, BlocklistAck::Error(_)));
+    assert!(!b.is_blocked("alice").blocked);
+}
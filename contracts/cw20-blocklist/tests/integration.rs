@@ -0,0 +1,383 @@
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+    Uint128,
+};
+use cw20::{AllowanceResponse, BalanceResponse, Cw20ReceiveMsg, TokenInfoResponse};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cw20_blocklist::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw20_blocklist::ContractError;
+
+const MINTER: &str = "creator";
+const ALICE: &str = "alice";
+const BOB: &str = "bob";
+
+/// A minimal cw20 receiver that records the last `Receive` callback, or fails on request.
+mod receiver {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum ExecuteMsg {
+        Receive(Cw20ReceiveMsg),
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    pub enum QueryMsg {
+        LastReceived {},
+    }
+
+    const LAST_RECEIVED: Item<Cw20ReceiveMsg> = Item::new("last_received");
+
+    pub fn instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::default())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Receive(receive) => {
+                if receive.msg == Binary::from(b"fail") {
+                    return Err(StdError::generic_err("receiver rejected tokens"));
+                }
+                LAST_RECEIVED.save(deps.storage, &receive)?;
+                Ok(Response::new().add_attribute("action", "receive"))
+            }
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::LastReceived {} => to_binary(&LAST_RECEIVED.may_load(deps.storage)?),
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+}
+
+fn blocklist_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw20_blocklist::contract::execute,
+        cw20_blocklist::contract::instantiate,
+        cw20_blocklist::contract::query,
+    ))
+}
+
+struct Suite {
+    app: App,
+    token: Addr,
+    receiver: Addr,
+}
+
+impl Suite {
+    /// Instantiates the token and a receiver, and mints 1000 tokens to `ALICE`.
+    fn new() -> Self {
+        let mut app = App::default();
+
+        let token_id = app.store_code(blocklist_contract());
+        let token = app
+            .instantiate_contract(
+                token_id,
+                Addr::unchecked(MINTER),
+                &InstantiateMsg {
+                    name: "Auto Gen".to_string(),
+                    symbol: "AUTO".to_string(),
+                    decimals: 6,
+                },
+                &[],
+                "token",
+                None,
+            )
+            .unwrap();
+
+        let receiver_id = app.store_code(receiver::contract());
+        let receiver = app
+            .instantiate_contract(
+                receiver_id,
+                Addr::unchecked(MINTER),
+                &Empty {},
+                &[],
+                "receiver",
+                None,
+            )
+            .unwrap();
+
+        let mut suite = Suite {
+            app,
+            token,
+            receiver,
+        };
+        suite
+            .execute(
+                MINTER,
+                ExecuteMsg::Mint {
+                    recipient: ALICE.to_string(),
+                    amount: Uint128::new(1000),
+                },
+            )
+            .unwrap();
+        suite
+    }
+
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg) -> Result<(), ContractError> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.token.clone(), &msg, &[])
+            .map(|_| ())
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn block(&mut self, address: &str) {
+        self.execute(
+            MINTER,
+            ExecuteMsg::AddToBlockedList {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    fn send_msg(&self, amount: u128, msg: &[u8]) -> ExecuteMsg {
+        ExecuteMsg::Send {
+            contract: self.receiver.to_string(),
+            amount: Uint128::new(amount),
+            msg: Binary::from(msg),
+        }
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.token,
+                &QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    }
+
+    fn total_supply(&self) -> Uint128 {
+        let res: TokenInfoResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.token, &QueryMsg::TokenInfo {})
+            .unwrap();
+        res.total_supply
+    }
+
+    fn allowance(&self, owner: &str, spender: &str) -> Uint128 {
+        let res: AllowanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.token,
+                &QueryMsg::Allowance {
+                    owner: owner.to_string(),
+                    spender: spender.to_string(),
+                },
+            )
+            .unwrap();
+        res.allowance
+    }
+
+    fn last_received(&self) -> Option<Cw20ReceiveMsg> {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.receiver, &receiver::QueryMsg::LastReceived {})
+            .unwrap()
+    }
+}
+
+#[test]
+fn send_triggers_receive_callback() {
+    let mut suite = Suite::new();
+
+    let msg = suite.send_msg(400, b"deposit");
+    suite.execute(ALICE, msg).unwrap();
+
+    assert_eq!(suite.balance(ALICE), Uint128::new(600));
+    assert_eq!(suite.balance(suite.receiver.as_str()), Uint128::new(400));
+    assert_eq!(
+        suite.last_received(),
+        Some(Cw20ReceiveMsg {
+            sender: ALICE.to_string(),
+            amount: Uint128::new(400),
+            msg: Binary::from(b"deposit"),
+        })
+    );
+}
+
+#[test]
+fn failing_receiver_reverts_send() {
+    let mut suite = Suite::new();
+
+    let msg = suite.send_msg(400, b"fail");
+    suite
+        .app
+        .execute_contract(Addr::unchecked(ALICE), suite.token.clone(), &msg, &[])
+        .unwrap_err();
+
+    assert_eq!(suite.balance(ALICE), Uint128::new(1000));
+    assert_eq!(suite.balance(suite.receiver.as_str()), Uint128::zero());
+    assert_eq!(suite.last_received(), None);
+}
+
+#[test]
+fn blocked_sender_cannot_send_or_transfer() {
+    let mut suite = Suite::new();
+    suite.block(ALICE);
+
+    let msg = suite.send_msg(400, b"deposit");
+    assert_eq!(suite.execute(ALICE, msg), Err(ContractError::Blocked {}));
+
+    let msg = ExecuteMsg::Transfer {
+        recipient: BOB.to_string(),
+        amount: Uint128::new(1),
+    };
+    assert_eq!(suite.execute(ALICE, msg), Err(ContractError::Blocked {}));
+
+    assert_eq!(suite.balance(ALICE), Uint128::new(1000));
+    assert_eq!(suite.last_received(), None);
+}
+
+#[test]
+fn blocked_receiver_contract_cannot_receive() {
+    let mut suite = Suite::new();
+    let receiver = suite.receiver.to_string();
+    suite.block(&receiver);
+
+    let msg = suite.send_msg(400, b"deposit");
+    assert_eq!(suite.execute(ALICE, msg), Err(ContractError::Blocked {}));
+    assert_eq!(suite.balance(&receiver), Uint128::zero());
+    assert_eq!(suite.last_received(), None);
+}
+
+#[test]
+fn allowance_flows() {
+    let mut suite = Suite::new();
+
+    suite
+        .execute(
+            ALICE,
+            ExecuteMsg::IncreaseAllowance {
+                spender: BOB.to_string(),
+                amount: Uint128::new(500),
+                expires: None,
+            },
+        )
+        .unwrap();
+
+    // The spender sends part of the allowance into the receiver contract.
+    suite
+        .execute(
+            BOB,
+            ExecuteMsg::SendFrom {
+                owner: ALICE.to_string(),
+                contract: suite.receiver.to_string(),
+                amount: Uint128::new(200),
+                msg: Binary::from(b"deposit"),
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.balance(ALICE), Uint128::new(800));
+    assert_eq!(suite.allowance(ALICE, BOB), Uint128::new(300));
+    assert_eq!(
+        suite.last_received().unwrap().sender,
+        BOB.to_string(),
+        "cw20-base reports the spender as the sender of a SendFrom"
+    );
+
+    // Once the owner is blocked, the allowance can no longer be spent.
+    suite.block(ALICE);
+    let err = suite
+        .execute(
+            BOB,
+            ExecuteMsg::TransferFrom {
+                owner: ALICE.to_string(),
+                recipient: BOB.to_string(),
+                amount: Uint128::new(100),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Blocked {});
+    assert_eq!(suite.allowance(ALICE, BOB), Uint128::new(300));
+    assert_eq!(suite.balance(BOB), Uint128::zero());
+
+    // Spending beyond the allowance fails as in cw20-base.
+    suite
+        .execute(
+            MINTER,
+            ExecuteMsg::RemoveFromBlockedList {
+                address: ALICE.to_string(),
+            },
+        )
+        .unwrap();
+    let err = suite
+        .execute(
+            BOB,
+            ExecuteMsg::TransferFrom {
+                owner: ALICE.to_string(),
+                recipient: BOB.to_string(),
+                amount: Uint128::new(301),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientAllowance {});
+}
+
+#[test]
+fn destroy_blocked_funds() {
+    let mut suite = Suite::new();
+
+    let msg = suite.send_msg(400, b"deposit");
+    suite.execute(ALICE, msg).unwrap();
+
+    // Only blocked funds can be destroyed, and only by the minter.
+    let destroy = ExecuteMsg::DestroyBlockedFunds {
+        address: ALICE.to_string(),
+    };
+    assert_eq!(
+        suite.execute(MINTER, destroy.clone()),
+        Err(ContractError::NotBlocked {})
+    );
+    suite.block(ALICE);
+    assert_eq!(
+        suite.execute(BOB, destroy.clone()),
+        Err(ContractError::Unauthorized {})
+    );
+
+    suite.execute(MINTER, destroy).unwrap();
+    assert_eq!(suite.balance(ALICE), Uint128::zero());
+    assert_eq!(suite.total_supply(), Uint128::new(400));
+
+    // Funds parked in a contract can be seized the same way.
+    let receiver = suite.receiver.to_string();
+    suite.block(&receiver);
+    suite
+        .execute(
+            MINTER,
+            ExecuteMsg::DestroyBlockedFunds {
+                address: receiver.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.balance(&receiver), Uint128::zero());
+    assert_eq!(suite.total_supply(), Uint128::zero());
+}
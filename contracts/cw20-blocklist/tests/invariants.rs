@@ -0,0 +1,593 @@
+//! Property-based state-machine tests over `contract::execute`.
+//!
+//! Random sequences of messages are run against both the contract and a simple model of the
+//! ledger. After every step we check that the contract agrees with the model, that the total
+//! supply equals the sum of all balances, and that blocked accounts only lose funds through
+//! `DestroyBlockedFunds`.
+
+use std::collections::BTreeMap;
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{from_binary, Addr, Binary, Deps, Order, OwnedDeps, StdError, Storage, Uint128};
+use cw20_base::state::{ALLOWANCES, BALANCES, TOKEN_INFO};
+use proptest::prelude::*;
+
+use cw20_blocklist::contract::{execute, instantiate, query};
+use cw20_blocklist::msg::{
+    CanTransferResponse, ExecuteMsg, InstantiateMsg, QueryMsg, StatsResponse,
+};
+use cw20_blocklist::state::{BALANCE_SNAPSHOTS, BLOCKED, TOTAL_SUPPLY_SNAPSHOTS};
+use cw20_blocklist::ContractError;
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+const MINTER: usize = 0;
+const ACTORS: [&str; 5] = ["creator", "addr0000", "addr0001", "addr0002", "spender"];
+
+#[derive(Clone, Debug)]
+enum Op {
+    Mint {
+        sender: usize,
+        to: usize,
+        amount: u128,
+    },
+    Transfer {
+        from: usize,
+        to: usize,
+        amount: u128,
+    },
+    Send {
+        from: usize,
+        to: usize,
+        amount: u128,
+    },
+    Redeem {
+        sender: usize,
+        amount: u128,
+    },
+    Block {
+        sender: usize,
+        address: usize,
+    },
+    Unblock {
+        sender: usize,
+        address: usize,
+    },
+    Destroy {
+        sender: usize,
+        address: usize,
+    },
+    IncreaseAllowance {
+        owner: usize,
+        spender: usize,
+        amount: u128,
+    },
+    DecreaseAllowance {
+        owner: usize,
+        spender: usize,
+        amount: u128,
+    },
+    TransferFrom {
+        spender: usize,
+        owner: usize,
+        to: usize,
+        amount: u128,
+    },
+}
+
+/// The expected result of a message, at the granularity of error variants.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Outcome {
+    Ok,
+    Unauthorized,
+    Blocked,
+    NotBlocked,
+    InvalidZeroAmount,
+    CannotSetOwnAccount,
+    NoAllowance,
+    InsufficientBalance,
+    InsufficientAllowance,
+    Overflow,
+    NotFound,
+}
+
+impl From<&ContractError> for Outcome {
+    fn from(err: &ContractError) -> Self {
+        match err {
+            ContractError::Unauthorized {} => Outcome::Unauthorized,
+            ContractError::Blocked {} => Outcome::Blocked,
+            ContractError::NotBlocked {} => Outcome::NotBlocked,
+            ContractError::InvalidZeroAmount {} => Outcome::InvalidZeroAmount,
+            ContractError::CannotSetOwnAccount {} => Outcome::CannotSetOwnAccount,
+            ContractError::NoAllowance {} => Outcome::NoAllowance,
+            ContractError::InsufficientBalance {} => Outcome::InsufficientBalance,
+            ContractError::InsufficientAllowance {} => Outcome::InsufficientAllowance,
+            ContractError::Std(StdError::Overflow { .. }) => Outcome::Overflow,
+            ContractError::Std(StdError::NotFound { .. }) => Outcome::NotFound,
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+fn actor() -> impl Strategy<Value = usize> {
+    0..ACTORS.len()
+}
+
+fn amount() -> impl Strategy<Value = u128> {
+    prop_oneof![Just(0u128), 1..2_000u128]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    // The minter is picked as sender most of the time so admin messages actually run.
+    let admin = prop_oneof![4 => Just(MINTER), 1 => actor()];
+    prop_oneof![
+        (admin.clone(), actor(), amount()).prop_map(|(sender, to, amount)| Op::Mint {
+            sender,
+            to,
+            amount
+        }),
+        (actor(), actor(), amount()).prop_map(|(from, to, amount)| Op::Transfer {
+            from,
+            to,
+            amount
+        }),
+        (actor(), actor(), amount()).prop_map(|(from, to, amount)| Op::Send { from, to, amount }),
+        (admin.clone(), amount()).prop_map(|(sender, amount)| Op::Redeem { sender, amount }),
+        (admin.clone(), actor()).prop_map(|(sender, address)| Op::Block { sender, address }),
+        (admin.clone(), actor()).prop_map(|(sender, address)| Op::Unblock { sender, address }),
+        (admin, actor()).prop_map(|(sender, address)| Op::Destroy { sender, address }),
+        (actor(), actor(), amount()).prop_map(|(owner, spender, amount)| {
+            Op::IncreaseAllowance {
+                owner,
+                spender,
+                amount,
+            }
+        }),
+        (actor(), actor(), amount()).prop_map(|(owner, spender, amount)| {
+            Op::DecreaseAllowance {
+                owner,
+                spender,
+                amount,
+            }
+        }),
+        (actor(), actor(), actor(), amount()).prop_map(|(spender, owner, to, amount)| {
+            Op::TransferFrom {
+                spender,
+                owner,
+                to,
+                amount,
+            }
+        }),
+    ]
+}
+
+/// A plain model of the ledger the contract is expected to implement.
+#[derive(Clone, Debug, Default)]
+struct Model {
+    balances: BTreeMap<usize, u128>,
+    allowances: BTreeMap<(usize, usize), u128>,
+    blocked: BTreeMap<usize, bool>,
+}
+
+impl Model {
+    fn balance(&self, who: usize) -> u128 {
+        self.balances.get(&who).copied().unwrap_or_default()
+    }
+
+    fn is_blocked(&self, who: usize) -> bool {
+        self.blocked.get(&who).copied().unwrap_or_default()
+    }
+
+    fn debit(&mut self, who: usize, amount: u128) -> Result<(), Outcome> {
+        let balance = self.balance(who);
+        if balance < amount {
+            return Err(Outcome::Overflow);
+        }
+        self.balances.insert(who, balance - amount);
+        Ok(())
+    }
+
+    fn credit(&mut self, who: usize, amount: u128) {
+        *self.balances.entry(who).or_default() += amount;
+    }
+
+    /// Applies `op`, mirroring the order of checks in the contract.
+    fn apply(&mut self, op: &Op) -> Result<(), Outcome> {
+        match *op {
+            Op::Mint { sender, to, amount } => {
+                if amount == 0 {
+                    return Err(Outcome::InvalidZeroAmount);
+                }
+                if sender != MINTER {
+                    return Err(Outcome::Unauthorized);
+                }
+                self.credit(to, amount);
+            }
+            Op::Transfer { from, to, amount } | Op::Send { from, to, amount } => {
+                if self.is_blocked(from) {
+                    return Err(Outcome::Blocked);
+                }
+                if matches!(op, Op::Send { .. }) && self.is_blocked(to) {
+                    return Err(Outcome::Blocked);
+                }
+                if amount == 0 {
+                    return Err(Outcome::InvalidZeroAmount);
+                }
+                if self.balance(from) < amount {
+                    return Err(Outcome::InsufficientBalance);
+                }
+                self.debit(from, amount)?;
+                self.credit(to, amount);
+            }
+            Op::Redeem { sender, amount } => {
+                if sender != MINTER {
+                    return Err(Outcome::Unauthorized);
+                }
+                if self.is_blocked(sender) {
+                    return Err(Outcome::Blocked);
+                }
+                if amount == 0 {
+                    return Err(Outcome::InvalidZeroAmount);
+                }
+                self.debit(sender, amount)?;
+            }
+            Op::Block { sender, address } | Op::Unblock { sender, address } => {
+                if sender != MINTER {
+                    return Err(Outcome::Unauthorized);
+                }
+                self.blocked.insert(address, matches!(op, Op::Block { .. }));
+            }
+            Op::Destroy { sender, address } => {
+                if sender != MINTER {
+                    return Err(Outcome::Unauthorized);
+                }
+                if !self.is_blocked(address) {
+                    return Err(Outcome::NotBlocked);
+                }
+                self.balances.insert(address, 0);
+            }
+            Op::IncreaseAllowance {
+                owner,
+                spender,
+                amount,
+            } => {
+                if owner == spender {
+                    return Err(Outcome::CannotSetOwnAccount);
+                }
+                *self.allowances.entry((owner, spender)).or_default() += amount;
+            }
+            Op::DecreaseAllowance {
+                owner,
+                spender,
+                amount,
+            } => {
+                if owner == spender {
+                    return Err(Outcome::CannotSetOwnAccount);
+                }
+                match self.allowances.get(&(owner, spender)).copied() {
+                    None => return Err(Outcome::NotFound),
+                    Some(current) if amount < current => {
+                        self.allowances.insert((owner, spender), current - amount);
+                    }
+                    Some(_) => {
+                        self.allowances.remove(&(owner, spender));
+                    }
+                }
+            }
+            Op::TransferFrom {
+                spender,
+                owner,
+                to,
+                amount,
+            } => {
+                if self.is_blocked(owner) {
+                    return Err(Outcome::Blocked);
+                }
+                let allowance = match self.allowances.get(&(owner, spender)) {
+                    None => return Err(Outcome::NoAllowance),
+                    Some(allowance) if *allowance < amount => {
+                        return Err(Outcome::InsufficientAllowance)
+                    }
+                    Some(allowance) => *allowance,
+                };
+                if self.balance(owner) < amount {
+                    return Err(Outcome::InsufficientBalance);
+                }
+                self.debit(owner, amount)?;
+                self.allowances.insert((owner, spender), allowance - amount);
+                self.credit(to, amount);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn to_msg(op: &Op) -> (usize, ExecuteMsg) {
+    let addr = |i: usize| ACTORS[i].to_string();
+    let amt = Uint128::new;
+    match *op {
+        Op::Mint { sender, to, amount } => (
+            sender,
+            ExecuteMsg::Mint {
+                recipient: addr(to),
+                amount: amt(amount),
+            },
+        ),
+        Op::Transfer { from, to, amount } => (
+            from,
+            ExecuteMsg::Transfer {
+                recipient: addr(to),
+                amount: amt(amount),
+            },
+        ),
+        Op::Send { from, to, amount } => (
+            from,
+            ExecuteMsg::Send {
+                contract: addr(to),
+                amount: amt(amount),
+                msg: Binary::default(),
+            },
+        ),
+        Op::Redeem { sender, amount } => (
+            sender,
+            ExecuteMsg::Redeem {
+                amount: amt(amount),
+            },
+        ),
+        Op::Block { sender, address } => (
+            sender,
+            ExecuteMsg::AddToBlockedList {
+                address: addr(address),
+            },
+        ),
+        Op::Unblock { sender, address } => (
+            sender,
+            ExecuteMsg::RemoveFromBlockedList {
+                address: addr(address),
+            },
+        ),
+        Op::Destroy { sender, address } => (
+            sender,
+            ExecuteMsg::DestroyBlockedFunds {
+                address: addr(address),
+            },
+        ),
+        Op::IncreaseAllowance {
+            owner,
+            spender,
+            amount,
+        } => (
+            owner,
+            ExecuteMsg::IncreaseAllowance {
+                spender: addr(spender),
+                amount: amt(amount),
+                expires: None,
+            },
+        ),
+        Op::DecreaseAllowance {
+            owner,
+            spender,
+            amount,
+        } => (
+            owner,
+            ExecuteMsg::DecreaseAllowance {
+                spender: addr(spender),
+                amount: amt(amount),
+                expires: None,
+            },
+        ),
+        Op::TransferFrom {
+            spender,
+            owner,
+            to,
+            amount,
+        } => (
+            spender,
+            ExecuteMsg::TransferFrom {
+                owner: addr(owner),
+                recipient: addr(to),
+                amount: amt(amount),
+            },
+        ),
+    }
+}
+
+/// Runs `op` like a transaction: on error every write is rolled back, as it is on chain.
+fn run(deps: &mut MockDeps, height: u64, op: &Op) -> Result<(), ContractError> {
+    let backup: Vec<_> = deps.storage.range(None, None, Order::Ascending).collect();
+
+    let (sender, msg) = to_msg(op);
+    let mut env = mock_env();
+    env.block.height = height;
+    let res = execute(deps.as_mut(), env, mock_info(ACTORS[sender], &[]), msg);
+
+    if res.is_err() {
+        let keys: Vec<_> = deps
+            .storage
+            .range(None, None, Order::Ascending)
+            .map(|(k, _)| k)
+            .collect();
+        for key in keys {
+            deps.storage.remove(&key);
+        }
+        for (key, value) in backup {
+            deps.storage.set(&key, &value);
+        }
+    }
+    res.map(|_| ())
+}
+
+fn balance(deps: Deps, who: usize) -> u128 {
+    BALANCES
+        .may_load(deps.storage, &Addr::unchecked(ACTORS[who]))
+        .unwrap()
+        .unwrap_or_default()
+        .u128()
+}
+
+/// Asks `CanTransfer` about a `Transfer` or `TransferFrom`; `None` for other ops.
+fn can_transfer(deps: Deps, op: &Op) -> Option<bool> {
+    let msg = match *op {
+        Op::Transfer { from, to, amount } => QueryMsg::CanTransfer {
+            from: ACTORS[from].to_string(),
+            to: ACTORS[to].to_string(),
+            amount: Uint128::new(amount),
+            spender: None,
+        },
+        Op::TransferFrom {
+            spender,
+            owner,
+            to,
+            amount,
+        } => QueryMsg::CanTransfer {
+            from: ACTORS[owner].to_string(),
+            to: ACTORS[to].to_string(),
+            amount: Uint128::new(amount),
+            spender: Some(ACTORS[spender].to_string()),
+        },
+        _ => return None,
+    };
+    let res: CanTransferResponse = from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.allowed, res.reason.is_none());
+    Some(res.allowed)
+}
+
+fn check_invariants(deps: Deps, model: &Model) {
+    let total_supply = TOKEN_INFO.load(deps.storage).unwrap().total_supply;
+
+    // Supply conservation.
+    let sum = BALANCES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.unwrap().1)
+        .fold(Uint128::zero(), |acc, balance| acc + balance);
+    assert_eq!(
+        total_supply, sum,
+        "total supply differs from sum of balances"
+    );
+
+    // Snapshots mirror the live state.
+    assert_eq!(
+        TOTAL_SUPPLY_SNAPSHOTS
+            .may_load(deps.storage)
+            .unwrap()
+            .unwrap_or_default(),
+        total_supply
+    );
+    for (address, balance) in BALANCES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.unwrap())
+    {
+        let snapshot = BALANCE_SNAPSHOTS
+            .may_load(deps.storage, &address)
+            .unwrap()
+            .unwrap_or_default();
+        assert_eq!(snapshot, balance, "snapshot out of sync for {}", address);
+    }
+
+    // Running totals match a full count.
+    let stats: StatsResponse =
+        from_binary(&query(deps, mock_env(), QueryMsg::Stats {}).unwrap()).unwrap();
+    let holders = BALANCES
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| !item.as_ref().unwrap().1.is_zero())
+        .count();
+    assert_eq!(stats.holders as usize, holders, "holder count");
+    let blocked: Vec<usize> = (0..ACTORS.len())
+        .filter(|who| model.is_blocked(*who))
+        .collect();
+    assert_eq!(
+        stats.blocked_addresses as usize,
+        blocked.len(),
+        "blocked count"
+    );
+    let blocked_balance: u128 = blocked.iter().map(|who| balance(deps, *who)).sum();
+    assert_eq!(
+        stats.blocked_balance.u128(),
+        blocked_balance,
+        "blocked balance"
+    );
+    assert_eq!(stats.total_supply, total_supply);
+
+    // The contract agrees with the model.
+    for (who, actor) in ACTORS.iter().enumerate() {
+        assert_eq!(
+            balance(deps, who),
+            model.balance(who),
+            "balance of {}",
+            actor
+        );
+        let blocked = BLOCKED
+            .may_load(deps.storage, &Addr::unchecked(*actor))
+            .unwrap()
+            .unwrap_or_default();
+        assert_eq!(blocked, model.is_blocked(who), "blocked flag of {}", actor);
+    }
+    for ((owner, spender), allowance) in &model.allowances {
+        let stored = ALLOWANCES
+            .load(
+                deps.storage,
+                (
+                    &Addr::unchecked(ACTORS[*owner]),
+                    &Addr::unchecked(ACTORS[*spender]),
+                ),
+            )
+            .unwrap();
+        assert_eq!(stored.allowance.u128(), *allowance);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn ledger_invariants_hold(ops in prop::collection::vec(op(), 1..60)) {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            name: "Auto Gen".to_string(),
+            symbol: "AUTO".to_string(),
+            decimals: 6,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(ACTORS[MINTER], &[]), msg).unwrap();
+
+        let mut model = Model::default();
+        for (step, op) in ops.iter().enumerate() {
+            let before: Vec<u128> = (0..ACTORS.len())
+                .map(|who| balance(deps.as_ref(), who))
+                .collect();
+            let blocked_before: Vec<bool> = (0..ACTORS.len())
+                .map(|who| model.is_blocked(who))
+                .collect();
+
+            let mut next = model.clone();
+            let expected = next.apply(op).err().unwrap_or(Outcome::Ok);
+
+            // The dry run predicts the outcome of every transfer.
+            if let Some(allowed) = can_transfer(deps.as_ref(), op) {
+                prop_assert_eq!(allowed, expected == Outcome::Ok, "step {}: {:?}", step, op);
+            }
+            let actual = match run(&mut deps, 12_345 + step as u64, op) {
+                Ok(()) => Outcome::Ok,
+                Err(err) => Outcome::from(&err),
+            };
+            prop_assert_eq!(actual, expected, "step {}: {:?}", step, op);
+            if actual == Outcome::Ok {
+                model = next;
+            }
+
+            check_invariants(deps.as_ref(), &model);
+
+            // Blocked accounts only ever lose funds to an admin seizure.
+            for who in 0..ACTORS.len() {
+                let seized = matches!(op, Op::Destroy { address, .. } if *address == who);
+                if blocked_before[who] && !seized {
+                    prop_assert!(
+                        balance(deps.as_ref(), who) >= before[who],
+                        "step {}: blocked {} lost funds via {:?}", step, ACTORS[who], op
+                    );
+                }
+            }
+        }
+    }
+}